@@ -0,0 +1,406 @@
+use core::cell::RefCell;
+
+use aidoku::{
+	alloc::{String, Vec},
+	prelude::*,
+	Chapter, ContentRating, FilterValue, Manga, MangaPageResult, Page, Result, Viewer,
+};
+
+use crate::helper::*;
+use crate::lang::{Language, SORT_ORDERS};
+use crate::net;
+use crate::transport::Transport;
+use crate::{BASE_URL, GENRE_MATCH_ANY, SCHEDULE_PAGES};
+
+/// Webtoons mobile API base URL for title info and all episodes in one request.
+const MOBILE_API: &str = "https://m.webtoons.com/api/v1";
+
+/// Upper bound on genre pages fetched when combining genre listings.
+///
+/// Combining a cut-off listing would give wrong results, so longer genres
+/// fail instead.
+const MAX_GENRE_PAGES: i32 = 20;
+
+/// The last listing merged client-side, kept so paging through it doesn't
+/// fetch every source page again.
+#[derive(Default)]
+pub struct ListingCache {
+	last: RefCell<Option<(String, Vec<Manga>)>>,
+}
+
+impl ListingCache {
+	/// Take a page of the listing cached under `key`, building it with
+	/// `fetch` when it isn't cached.
+	///
+	/// The first page always fetches, so refreshing a listing starts over.
+	pub fn page(
+		&self,
+		key: &str,
+		page: i32,
+		fetch: impl FnOnce() -> Result<Vec<Manga>>,
+	) -> Result<MangaPageResult> {
+		let mut last = self.last.borrow_mut();
+		let cached = page > 1
+			&& last
+				.as_ref()
+				.is_some_and(|(cached_key, _)| cached_key == key);
+		if !cached {
+			*last = None;
+			*last = Some((String::from(key), fetch()?));
+		}
+		let entries = last
+			.as_ref()
+			.map(|(_, entries)| entries.as_slice())
+			.unwrap_or_default();
+		Ok(paginate(entries, page))
+	}
+}
+
+/// The source's requests and flows for one language edition, sent through a
+/// [`Transport`] so they run the same against the site and recordings.
+pub struct Client<T: Transport> {
+	pub transport: T,
+	pub lang: &'static Language,
+}
+
+impl<T: Transport> Client<T> {
+	pub fn new(transport: T, lang: &'static Language) -> Self {
+		Self { transport, lang }
+	}
+
+	/// Fetch and parse an HTML page.
+	pub fn get_html(&self, url: &str) -> Result<T::Document> {
+		net::get_html(&self.transport, url)
+	}
+
+	/// Fetch a response body as text.
+	pub fn get_string(&self, url: &str) -> Result<String> {
+		net::get_string(&self.transport, url)
+	}
+
+	/// Fetch a page and parse manga items.
+	pub fn fetch_manga_list(&self, url: &str) -> Result<(Vec<Manga>, bool)> {
+		let html = self.get_html(url)?;
+		Ok(parse_manga_list(self.lang, &html))
+	}
+
+	/// Fetch every Originals title across all genres.
+	///
+	/// The site has no all-genres listing, so the weekday and completed pages,
+	/// each sorted per `sort_order`, are interleaved here. Every day's first
+	/// titles come first, but the result has no overall order.
+	pub fn fetch_all_originals(&self, sort_order: &str) -> Result<Vec<Manga>> {
+		let lang_path = self.lang.path;
+		let mut lists: Vec<Vec<Manga>> = Vec::new();
+		for day in SCHEDULE_PAGES {
+			let url = format!("{BASE_URL}{lang_path}/originals/{day}?sortOrder={sort_order}");
+			let (entries, _) = self.fetch_manga_list(&url)?;
+			lists.push(entries);
+		}
+		Ok(merge_listings(lists))
+	}
+
+	/// Fetch every page of a genre listing, sorted per `sort_order`.
+	///
+	/// Fails on genres with more than [`MAX_GENRE_PAGES`] pages.
+	pub fn fetch_genre(&self, genre_slug: &str, sort_order: &str) -> Result<Vec<Manga>> {
+		let lang_path = self.lang.path;
+		let mut entries: Vec<Manga> = Vec::new();
+		for page in 1..=MAX_GENRE_PAGES {
+			let url = format!(
+				"{BASE_URL}{lang_path}/genres/{genre_slug}?sortOrder={sort_order}&page={page}"
+			);
+			let (mut results, has_next_page) = self.fetch_manga_list(&url)?;
+			entries.append(&mut results);
+			if !has_next_page {
+				return Ok(entries);
+			}
+		}
+		bail!("The {genre_slug} genre has too many pages to combine with other genres")
+	}
+
+	/// Combine the included genre listings, then drop every title listed under
+	/// an excluded genre.
+	fn combine_genres(
+		&self,
+		included: &[&GenreEntry],
+		excluded: &[&GenreEntry],
+		match_all: bool,
+		sort_order: &str,
+	) -> Result<Vec<Manga>> {
+		let mut entries = if included.is_empty() {
+			self.fetch_all_originals(sort_order)?
+		} else {
+			let mut lists: Vec<Vec<Manga>> = Vec::new();
+			for (_, slug) in included {
+				lists.push(self.fetch_genre(slug, sort_order)?);
+			}
+			if match_all {
+				intersect_listings(lists)
+			} else {
+				merge_listings(lists)
+			}
+		};
+		for genre in excluded {
+			let excluded_entries = self.fetch_genre(&genre.1, sort_order)?;
+			entries.retain(|manga: &Manga| {
+				!has_genre_tag(self.lang, manga, genre)
+					&& !excluded_entries.iter().any(|m: &Manga| m.key == manga.key)
+			});
+		}
+		Ok(entries)
+	}
+
+	/// Search by keyword, or browse by genre when there is none.
+	///
+	/// `genres` are the genres the site offers, used to resolve filter values.
+	/// Listings merged client-side are kept in `cache` while they're paged.
+	pub fn search(
+		&self,
+		cache: &ListingCache,
+		genres: &[GenreEntry],
+		query: Option<String>,
+		page: i32,
+		filters: Vec<FilterValue>,
+	) -> Result<MangaPageResult> {
+		let lang = self.lang;
+		let lang_path = lang.path;
+
+		let mut included_values: Vec<String> = Vec::new();
+		let mut excluded_values: Vec<String> = Vec::new();
+		let mut match_all = true;
+		let mut sort_order = SORT_ORDERS[0];
+
+		for filter in filters {
+			match filter {
+				FilterValue::Select { id, value } => {
+					if id == "genre" {
+						included_values.push(value);
+					} else if id == "genre_match" {
+						match_all = value != GENRE_MATCH_ANY;
+					} else if id == "sort" {
+						sort_order = lang.sort_order(&value);
+					}
+				}
+				FilterValue::MultiSelect {
+					id,
+					included,
+					excluded,
+				} => {
+					if id == "genres" {
+						included_values.extend(included);
+						excluded_values.extend(excluded);
+					}
+				}
+				_ => {}
+			}
+		}
+
+		let mut included: Vec<&GenreEntry> = Vec::new();
+		for value in &included_values {
+			included.extend(genre_name_to_slug(lang, genres, value)?);
+		}
+		let mut excluded: Vec<&GenreEntry> = Vec::new();
+		for value in &excluded_values {
+			excluded.extend(genre_name_to_slug(lang, genres, value)?);
+		}
+		// Browsing every genre instead of the stale ones selected would be wrong
+		let selects_genre = |value: &String| !is_all_genres(lang, value);
+		if included.is_empty() && included_values.iter().any(selects_genre) {
+			bail!("The selected genres are no longer offered by Webtoons");
+		}
+
+		if let Some(keyword) = query {
+			// The site doesn't sort search results, and the cards don't show
+			// what they'd be sorted by, so only the default order is offered.
+			// They do carry each title's genre, so the genre filters are
+			// applied to them here.
+			if sort_order != SORT_ORDERS[0] {
+				bail!("Search results can't be sorted, clear the sort filter to search by keyword");
+			}
+			let keyword = encode_uri_component(&keyword);
+			let mut entries: Vec<Manga> = Vec::new();
+			let mut has_next_page = false;
+			for catalog in ["originals", "canvas"] {
+				let url = format!(
+					"{BASE_URL}{lang_path}/search/{catalog}?keyword={keyword}&page={page}"
+				);
+				let (mut results, has_next) = self.fetch_manga_list(&url)?;
+				entries.append(&mut results);
+				has_next_page |= has_next;
+			}
+
+			entries.retain(|manga: &Manga| {
+				let has = |genre: &&GenreEntry| has_genre_tag(lang, manga, genre);
+				let matches = if match_all {
+					included.iter().all(has)
+				} else {
+					included.is_empty() || included.iter().any(has)
+				};
+				matches && !excluded.iter().any(has)
+			});
+
+			return Ok(MangaPageResult {
+				entries,
+				has_next_page,
+			});
+		}
+
+		match (included.as_slice(), excluded.is_empty()) {
+			([], true) => {
+				let key = format!("{}:originals:{sort_order}", lang.id);
+				cache.page(&key, page, || self.fetch_all_originals(sort_order))
+			}
+			([(_, genre_slug)], true) => {
+				let url = format!(
+					"{BASE_URL}{lang_path}/genres/{genre_slug}?sortOrder={sort_order}&page={page}"
+				);
+
+				let (entries, has_next_page) = self.fetch_manga_list(&url)?;
+
+				Ok(MangaPageResult {
+					entries,
+					has_next_page,
+				})
+			}
+			_ => {
+				// Every genre page is fetched up front, so the combined listing
+				// is kept while it's paged
+				let slugs = |genres: &[&GenreEntry]| {
+					genres
+						.iter()
+						.map(|(_, slug)| slug.as_str())
+						.collect::<Vec<&str>>()
+						.join(",")
+				};
+				let key = format!(
+					"{}:genres:{}:{}:{match_all}:{sort_order}",
+					lang.id,
+					slugs(&included),
+					slugs(&excluded)
+				);
+				cache.page(&key, page, || {
+					self.combine_genres(&included, &excluded, match_all, sort_order)
+				})
+			}
+		}
+	}
+
+	/// Scrape manga details from the desktop series page.
+	fn update_details_from_html(
+		&self,
+		title_type: TitleType,
+		title_no: &str,
+		manga: &mut Manga,
+	) -> Result<()> {
+		let lang_path = self.lang.path;
+		let detail_url = if let Some(ref url) = manga.url {
+			url.clone()
+		} else {
+			format!(
+				"{BASE_URL}{lang_path}/{}/a/list?title_no={title_no}",
+				title_type.web_path()
+			)
+		};
+
+		let html = self.get_html(&detail_url)?;
+		// Details already known from listings are kept behind an interstitial;
+		// without any, it's reported instead of an empty series
+		if is_age_gate(&html) {
+			// Served despite the age-gate cookie, so the title is age restricted
+			if manga.title.is_empty() {
+				bail!("Webtoons showed an age verification page for this series");
+			}
+			manga.content_rating = ContentRating::NSFW;
+		} else if is_consent_page(&html) {
+			// Says nothing about the title, so its rating is left as it was
+			if manga.title.is_empty() {
+				bail!("Webtoons showed a cookie consent page for this series");
+			}
+		} else {
+			parse_manga_details(self.lang, &html, manga);
+		}
+		Ok(())
+	}
+
+	/// Fill in the details and chapters of a manga, as requested.
+	pub fn manga_update(
+		&self,
+		mut manga: Manga,
+		needs_details: bool,
+		needs_chapters: bool,
+	) -> Result<Manga> {
+		let (_, title_type, title_no) = parse_manga_key(&manga.key);
+		let title_no = String::from(title_no);
+		let lang = self.lang;
+
+		if needs_details {
+			// Prefer the mobile API; the desktop page is only scraped when it fails
+			let api_url = format!("{MOBILE_API}/{}/{title_no}", title_type.api_path());
+			match self
+				.get_string(&api_url)
+				.and_then(|body: String| parse_title_info_json(&body))
+			{
+				Ok(info) => apply_title_info(lang, info, &mut manga),
+				Err(_) => self.update_details_from_html(title_type, &title_no, &mut manga)?,
+			}
+			manga.viewer = Viewer::Webtoon;
+		}
+
+		if needs_chapters {
+			// Use Webtoons mobile API to get ALL chapters in one request.
+			// Endpoint: m.webtoons.com/api/v1/{webtoon|canvas}/{titleId}/episodes?pageSize=99999
+			let api_url = format!(
+				"{MOBILE_API}/{}/{title_no}/episodes?pageSize=99999",
+				title_type.api_path()
+			);
+
+			let body = self.get_string(&api_url)?;
+
+			let chapters = parse_episodes_json(lang, &title_no, &body)?;
+
+			manga.chapters = Some(chapters);
+		}
+
+		Ok(manga)
+	}
+
+	/// Fetch the images of a chapter, splitting those taller than
+	/// `max_tile_height` into tiles.
+	pub fn page_list(
+		&self,
+		manga: &Manga,
+		chapter: &Chapter,
+		max_tile_height: Option<u32>,
+	) -> Result<Vec<Page>> {
+		let episode = parse_chapter_key(&chapter.key);
+		// The site redirects placeholder slugs to the real viewer page
+		let rebuilt_url = episode.map(|(title_no, episode_no)| {
+			let (_, title_type, _) = parse_manga_key(&manga.key);
+			format!(
+				"{BASE_URL}{}/{}/a/a/viewer?title_no={title_no}&episode_no={episode_no}",
+				self.lang.path,
+				title_type.web_path()
+			)
+		});
+		// The stored viewer link is only trusted for the episode the key names
+		let stored_url = chapter.url.as_ref().filter(|url: &&String| match episode {
+			Some((title_no, episode_no)) => {
+				extract_title_no(url).as_deref() == Some(title_no)
+					&& extract_episode_no(url) == episode_no.parse().ok()
+			}
+			None => true,
+		});
+
+		let html = match (stored_url, rebuilt_url) {
+			// A stored link breaks when a series or episode slug is renamed
+			(Some(url), Some(rebuilt_url)) => {
+				self.get_html(url).or_else(|_| self.get_html(&rebuilt_url))?
+			}
+			(Some(url), None) => self.get_html(url)?,
+			(None, Some(rebuilt_url)) => self.get_html(&rebuilt_url)?,
+			(None, None) => self.get_html(&chapter.key)?,
+		};
+		parse_viewer_pages(&html, max_tile_height)
+	}
+}
@@ -1,45 +1,596 @@
+use core::cell::RefCell;
+
 use aidoku::{
 	alloc::{String, Vec},
-	imports::html::Element,
-	Chapter, Manga, Viewer,
+	prelude::*,
+	Chapter, ContentRating, Manga, MangaPageResult, MangaStatus, Page, PageContent, PageContext,
+	Result, Viewer,
 };
+use serde::de::DeserializeOwned;
+use zune_core::{colorspace::ColorSpace, options::DecoderOptions};
+use zune_jpeg::JpegDecoder;
+
+use crate::lang::{Language, LANGUAGES, ZH_HANT};
+use crate::models::{ApiError, ApiResponse, Episode, EpisodeListResult, TitleInfo, TitleInfoResult};
+use crate::node::Node;
+
+/// Filter id of the "all genres" option.
+pub const ALL_GENRES_ID: &str = "all";
+
+/// A genre offered by the site, as `(display name, URL slug)`.
+pub type GenreEntry = (String, String);
+
+/// The built-in genre table of a language edition.
+pub fn static_genres(lang: &Language) -> Vec<GenreEntry> {
+	lang.genres
+		.iter()
+		.map(|(name, slug)| (String::from(*name), String::from(*slug)))
+		.collect()
+}
+
+/// Whether a genre filter value is the "all genres" option.
+pub fn is_all_genres(lang: &Language, name: &str) -> bool {
+	name.is_empty() || name == ALL_GENRES_ID || name == lang.all_genres
+}
+
+/// Map a genre filter value (display name or slug) to the Webtoons URL slug.
+///
+/// Returns `None` for the "all genres" option and for built-in genres the
+/// site no longer offers, which saved filters may still select, and fails on
+/// other unknown genres.
+pub fn genre_name_to_slug<'a>(
+	lang: &Language,
+	genres: &'a [GenreEntry],
+	name: &str,
+) -> Result<Option<&'a GenreEntry>> {
+	if is_all_genres(lang, name) {
+		return Ok(None);
+	}
+	if let Some(genre) = genres
+		.iter()
+		.find(|(genre_name, slug)| genre_name == name || slug == name)
+	{
+		return Ok(Some(genre));
+	}
+	let is_static = lang
+		.genres
+		.iter()
+		.any(|(genre_name, slug)| *genre_name == name || *slug == name);
+	if is_static {
+		return Ok(None);
+	}
+	bail!("Unknown genre: {name}")
+}
 
-/// Map genre name (Chinese) to the Webtoons URL slug.
-pub fn genre_name_to_slug(name: &str) -> &'static str {
-	match name {
-		"愛情" => "romance",
-		"歐式宮廷" => "western_palace",
-		"影視化" => "adaptation",
-		"校園" => "school",
-		"台灣原創作品" => "local",
-		"奇幻冒險" => "fantasy",
-		"驚悚" => "thriller",
-		"恐怖" => "horror",
-		"武俠" => "martial_arts",
-		"LGBTQ+" => "bl_gl",
-		"大人系" => "romance_m",
-		"劇情" => "drama",
-		"動作" => "action",
-		"生活/日常" => "slice_of_life",
-		"搞笑" => "comedy",
-		"穿越/轉生" => "time_slip",
-		"現代/職場" => "city_office",
-		"懸疑推理" => "mystery",
-		"療癒/萌系" => "heartwarming",
-		"少年" => "shonen",
-		"古代宮廷" => "eastern_palace",
-		"小說" => "web_novel",
-		_ => "romance",
-	}
-}
-
-/// Extract `title_no` from a Webtoons URL.
+/// Parse the genre navigation of the `/genres` page.
+///
+/// Expected HTML structure:
+/// ```html
+/// <ul class="snb _genre">
+///   <li data-genre="FANTASY"><a href="/zh-hant/genres/fantasy">奇幻冒險</a></li>
+/// </ul>
+/// ```
+pub fn parse_genre_nav<N: Node>(html: &N) -> Vec<GenreEntry> {
+	let mut genres: Vec<GenreEntry> = Vec::new();
+	for link in html.select("ul._genre li a, .snb li a[href*='/genres/']") {
+		let Some(href) = link.attr("href") else {
+			continue;
+		};
+		let path = href.split(['?', '#']).next().unwrap_or_default();
+		let Some((_, slug)) = path.trim_end_matches('/').rsplit_once("/genres/") else {
+			continue;
+		};
+		let name = link.text().unwrap_or_default();
+		if slug.is_empty() || slug.contains('/') || name.is_empty() {
+			continue;
+		}
+		if !genres.iter().any(|(_, s)| s == slug) {
+			genres.push((name, String::from(slug)));
+		}
+	}
+	genres
+}
+
+/// Merge several listings into one, taking entries from each in turn so every
+/// list keeps its own order, and dropping duplicate keys.
+pub fn merge_listings(lists: Vec<Vec<Manga>>) -> Vec<Manga> {
+	let mut iters: Vec<_> = lists.into_iter().map(|list: Vec<Manga>| list.into_iter()).collect();
+	let mut merged: Vec<Manga> = Vec::new();
+	loop {
+		let mut exhausted = true;
+		for iter in iters.iter_mut() {
+			if let Some(manga) = iter.next() {
+				exhausted = false;
+				if !merged.iter().any(|m: &Manga| m.key == manga.key) {
+					merged.push(manga);
+				}
+			}
+		}
+		if exhausted {
+			return merged;
+		}
+	}
+}
+
+/// Keep only the entries present in every listing, in the order of the first.
+pub fn intersect_listings(lists: Vec<Vec<Manga>>) -> Vec<Manga> {
+	let mut lists = lists.into_iter();
+	let Some(first) = lists.next() else {
+		return Vec::new();
+	};
+	let others: Vec<Vec<Manga>> = lists.collect();
+	let mut intersection: Vec<Manga> = Vec::new();
+	for manga in first {
+		let in_all = others
+			.iter()
+			.all(|list: &Vec<Manga>| list.iter().any(|m: &Manga| m.key == manga.key));
+		if in_all && !intersection.iter().any(|m: &Manga| m.key == manga.key) {
+			intersection.push(manga);
+		}
+	}
+	intersection
+}
+
+/// Whether a manga is tagged with a genre, by the site's current name or the
+/// built-in one.
+pub fn has_genre_tag(lang: &Language, manga: &Manga, genre: &GenreEntry) -> bool {
+	let (name, slug) = genre;
+	let static_name = lang.genre_name(slug);
+	manga.tags.as_ref().is_some_and(|tags: &Vec<String>| {
+		tags.iter()
+			.any(|tag: &String| tag == name || Some(tag.as_str()) == static_name)
+	})
+}
+
+/// Number of entries per page when paging a merged listing client-side.
+pub const MERGED_PAGE_SIZE: usize = 30;
+
+/// Take one page out of a merged listing.
+pub fn paginate(entries: &[Manga], page: i32) -> MangaPageResult {
+	let start = (page.max(1) as usize - 1) * MERGED_PAGE_SIZE;
+	let has_next_page = entries.len() > start + MERGED_PAGE_SIZE;
+	let entries = entries
+		.iter()
+		.skip(start)
+		.take(MERGED_PAGE_SIZE)
+		.cloned()
+		.collect();
+	MangaPageResult {
+		entries,
+		has_next_page,
+	}
+}
+
+/// Prefix of manga keys for Canvas (挑戰聯盟) series.
+///
+/// Originals keep the bare `title_no` as their key so existing library
+/// entries stay valid; Canvas uses its own `title_no` space, so its keys are
+/// prefixed to keep the two catalogs from colliding.
+pub const CANVAS_KEY_PREFIX: &str = "canvas:";
+
+/// The catalog a series belongs to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TitleType {
+	Originals,
+	Canvas,
+}
+
+impl TitleType {
+	/// Detect the catalog from a list, viewer or search result URL.
+	pub fn from_url(url: &str) -> Self {
+		if url.contains("/challenge/") || url.contains("/canvas/") {
+			TitleType::Canvas
+		} else {
+			TitleType::Originals
+		}
+	}
+
+	/// Path segment used by desktop list and viewer URLs.
+	pub fn web_path(self) -> &'static str {
+		match self {
+			TitleType::Originals => "originals",
+			TitleType::Canvas => "challenge",
+		}
+	}
+
+	/// Path segment used by the mobile API.
+	pub fn api_path(self) -> &'static str {
+		match self {
+			TitleType::Originals => "webtoon",
+			TitleType::Canvas => "canvas",
+		}
+	}
+}
+
+/// Build the manga key for a `title_no` in the given edition and catalog.
+///
+/// Each edition numbers its titles separately, so keys outside Traditional
+/// Chinese start with the edition, e.g. `en:95` or `en:canvas:700123`.
+/// Traditional Chinese keys stay bare so existing library entries stay valid.
+pub fn make_manga_key(lang: &Language, title_type: TitleType, title_no: &str) -> String {
+	let key = match title_type {
+		TitleType::Originals => String::from(title_no),
+		TitleType::Canvas => format!("{CANVAS_KEY_PREFIX}{title_no}"),
+	};
+	if lang.id == ZH_HANT.id {
+		key
+	} else {
+		format!("{}:{key}", lang.id)
+	}
+}
+
+/// Split a manga key into its edition, catalog and `title_no`.
+pub fn parse_manga_key(key: &str) -> (&'static Language, TitleType, &str) {
+	let edition = key.split_once(':').and_then(|(id, rest)| {
+		let lang = LANGUAGES.iter().find(|lang: &&&Language| lang.id == id)?;
+		Some((*lang, rest))
+	});
+	let (lang, key) = edition.unwrap_or((&ZH_HANT, key));
+	match key.strip_prefix(CANVAS_KEY_PREFIX) {
+		Some(title_no) => (lang, TitleType::Canvas, title_no),
+		None => (lang, TitleType::Originals, key),
+	}
+}
+
+/// Percent-encode a string for use as a URL query value.
+pub fn encode_uri_component(value: &str) -> String {
+	const HEX: &[u8; 16] = b"0123456789ABCDEF";
+	let mut encoded = String::with_capacity(value.len());
+	for byte in value.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+				encoded.push(byte as char);
+			}
+			_ => {
+				encoded.push('%');
+				encoded.push(HEX[(byte >> 4) as usize] as char);
+				encoded.push(HEX[(byte & 0x0F) as usize] as char);
+			}
+		}
+	}
+	encoded
+}
+
+/// Extract a query parameter such as `title_no` from a Webtoons URL.
+pub fn extract_query_param(url: &str, name: &str) -> Option<String> {
+	let query = &url[url.find('?')? + 1..];
+	query
+		.split('&')
+		.filter_map(|pair: &str| pair.split_once('='))
+		.find(|(key, _)| *key == name)
+		.map(|(_, value)| String::from(value))
+}
+
+/// Extract `title_no` from a Webtoons URL (`titleNo` in app links).
 pub fn extract_title_no(url: &str) -> Option<String> {
-	let pos = url.find("title_no=")?;
-	let start = pos + 9;
-	let rest = &url[start..];
-	let end = rest.find('&').unwrap_or(rest.len());
-	Some(String::from(&rest[..end]))
+	extract_query_param(url, "title_no")
+		.or_else(|| extract_query_param(url, "titleNo"))
+		.filter(|title_no: &String| !title_no.is_empty())
+}
+
+/// Extract `episode_no` from a Webtoons URL (`episodeNo` in app links).
+pub fn extract_episode_no(url: &str) -> Option<i32> {
+	extract_query_param(url, "episode_no")
+		.or_else(|| extract_query_param(url, "episodeNo"))?
+		.parse()
+		.ok()
+}
+
+/// Decode a percent-encoded URL component.
+pub fn decode_uri_component(value: &str) -> String {
+	let bytes = value.as_bytes();
+	let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+	let hex = |b: u8| (b as char).to_digit(16);
+	let mut i = 0;
+	while i < bytes.len() {
+		match bytes[i] {
+			b'%' if i + 2 < bytes.len() => {
+				match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+					(Some(high), Some(low)) => {
+						decoded.push((high * 16 + low) as u8);
+						i += 3;
+						continue;
+					}
+					_ => decoded.push(b'%'),
+				}
+			}
+			b'+' => decoded.push(b' '),
+			byte => decoded.push(byte),
+		}
+		i += 1;
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Turn mobile, app-share and percent-encoded links into a plain Webtoons URL.
+///
+/// Share links wrap the real URL in a `url` (or `link`) query parameter, and
+/// some apps percent-encode the whole query string.
+pub fn normalize_deep_link(url: &str) -> String {
+	let mut url = String::from(url);
+	// Unwrap nested share links, and decode an encoded `?title_no%3D...` query
+	for _ in 0..3 {
+		let lower = url.to_ascii_lowercase();
+		if let Some(inner) =
+			extract_query_param(&url, "url").or_else(|| extract_query_param(&url, "link"))
+		{
+			url = decode_uri_component(&inner);
+		} else if lower.contains("%3d") || lower.contains("%3f") {
+			url = decode_uri_component(&url);
+		} else {
+			break;
+		}
+	}
+	url.replace("://m.webtoons.com", "://www.webtoons.com")
+}
+
+/// The language edition a Webtoons URL is in, from its first path segment.
+pub fn url_language(url: &str) -> Option<&'static Language> {
+	let edition = url_path_segments(url).into_iter().next()?;
+	LANGUAGES.iter().find(|lang: &&&Language| lang.id == edition).copied()
+}
+
+/// Path segments of a URL, without scheme, host, query and fragment.
+pub fn url_path_segments(url: &str) -> Vec<&str> {
+	let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+	let path = without_scheme
+		.split(['?', '#'])
+		.next()
+		.unwrap_or_default();
+	path.split('/')
+		.skip(1)
+		.filter(|segment: &&str| !segment.is_empty())
+		.collect()
+}
+
+/// Build a chapter key of the form `title_no/episode_no`.
+///
+/// Unlike viewer URLs, these keys survive series and episode slug renames.
+/// They're only looked up under their manga, whose key names the edition.
+pub fn make_chapter_key(title_no: &str, episode_no: i32) -> String {
+	format!("{title_no}/{episode_no}")
+}
+
+/// Split a `title_no/episode_no` chapter key.
+pub fn parse_chapter_key(key: &str) -> Option<(&str, &str)> {
+	let (title_no, episode_no) = key.split_once('/')?;
+	let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b: u8| b.is_ascii_digit());
+	(is_number(title_no) && is_number(episode_no)).then_some((title_no, episode_no))
+}
+
+/// Map a chapter key from older versions (the viewer URL, or the bare
+/// episode number when there was none) to the `title_no/episode_no` form.
+pub fn migrate_chapter_key(title_no: &str, key: &str) -> Option<String> {
+	if parse_chapter_key(key).is_some() {
+		return Some(String::from(key));
+	}
+	let episode_no: i32 = match extract_query_param(key, "episode_no") {
+		Some(episode_no) => episode_no.parse().ok()?,
+		None => key.parse().ok()?,
+	};
+	let title_no = extract_title_no(key).unwrap_or_else(|| String::from(title_no));
+	Some(make_chapter_key(&title_no, episode_no))
+}
+
+/// A credit role as labelled on Webtoons.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+	Writer,
+	Artist,
+	/// Author of the novel or work a title is adapted from.
+	Original,
+}
+
+/// Writers and illustrators of a title, with original work credits kept
+/// among the authors as `Name (label)`.
+#[derive(Default)]
+pub struct Credits {
+	pub authors: Vec<String>,
+	pub artists: Vec<String>,
+}
+
+impl Credits {
+	/// Add every name in `text` under `role`, honouring `Role: Name` labels.
+	///
+	/// A label applies to the names following it, e.g. `原著：A、B`.
+	pub fn add(&mut self, lang: &Language, text: &str, role: Role) {
+		let mut current = role;
+		for part in text.split([',', '/', '、']) {
+			match split_role(lang, part) {
+				Some((role, name)) => {
+					current = role;
+					self.push(lang, role, name);
+				}
+				None => self.push(lang, current, part.trim()),
+			}
+		}
+	}
+
+	fn push(&mut self, lang: &Language, role: Role, name: &str) {
+		if name.is_empty() {
+			return;
+		}
+		let (list, name) = match role {
+			Role::Writer => (&mut self.authors, String::from(name)),
+			Role::Artist => (&mut self.artists, String::from(name)),
+			Role::Original => (&mut self.authors, format!("{name} ({})", lang.original_label)),
+		};
+		if !list.contains(&name) {
+			list.push(name);
+		}
+	}
+
+	/// Keep the original work credits among `previous` authors, e.g. from a
+	/// listing item, when none were added; they go before the other authors.
+	pub fn keep_originals(&mut self, lang: &Language, previous: Option<&[String]>) {
+		let suffix = format!(" ({})", lang.original_label);
+		let is_original = |name: &&String| name.ends_with(&suffix);
+		if self.authors.iter().any(|name: &String| is_original(&name)) {
+			return;
+		}
+		let mut authors: Vec<String> = previous
+			.unwrap_or_default()
+			.iter()
+			.filter(is_original)
+			.cloned()
+			.collect();
+		authors.append(&mut self.authors);
+		self.authors = authors;
+	}
+
+	/// Store the credits on a manga, leaving fields without credits untouched.
+	pub fn apply(self, manga: &mut Manga) {
+		if !self.authors.is_empty() {
+			manga.authors = Some(self.authors);
+		}
+		if !self.artists.is_empty() {
+			manga.artists = Some(self.artists);
+		}
+	}
+}
+
+/// Split a `Role: Name` or `Role：Name` credit into its role and name.
+fn split_role<'a>(lang: &Language, part: &'a str) -> Option<(Role, &'a str)> {
+	let (label, name) = part.split_once([':', '：'])?;
+	let label = label.trim();
+	let role = if lang.original_roles.iter().any(|r| label.eq_ignore_ascii_case(r)) {
+		Role::Original
+	} else if lang.artist_roles.iter().any(|r| label.eq_ignore_ascii_case(r)) {
+		Role::Artist
+	} else if lang.writer_roles.iter().any(|r| label.eq_ignore_ascii_case(r)) {
+		Role::Writer
+	} else {
+		return None;
+	};
+	Some((role, name.trim()))
+}
+
+/// Parse a credit line from a listing item or series page.
+///
+/// Labelled credits (`原著：A / 作畫：B`) are sorted by role. Unlabelled
+/// `A / B` lines follow the site's writer / illustrator order, and a lone
+/// unlabelled name is credited as both writer and illustrator. Other
+/// unlabelled lines, such as `A, B, C`, are credited to writers only.
+pub fn parse_credits(lang: &Language, text: &str) -> Credits {
+	let groups: Vec<&str> = text
+		.split('/')
+		.map(|group: &str| group.trim())
+		.filter(|group: &&str| !group.is_empty())
+		.collect();
+	let labelled = groups
+		.iter()
+		.any(|group: &&str| split_role(lang, group).is_some());
+
+	let mut credits = Credits::default();
+	match groups.as_slice() {
+		_ if labelled => {
+			for group in &groups {
+				credits.add(lang, group, Role::Writer);
+			}
+		}
+		[single] if !single.contains([',', '、']) => {
+			credits.add(lang, single, Role::Writer);
+			credits.add(lang, single, Role::Artist);
+		}
+		[writers, artists] => {
+			credits.add(lang, writers, Role::Writer);
+			credits.add(lang, artists, Role::Artist);
+		}
+		_ => {
+			for group in &groups {
+				credits.add(lang, group, Role::Writer);
+			}
+		}
+	}
+	credits
+}
+
+/// Whether a page is an age verification interstitial instead of the
+/// requested series or viewer page.
+pub fn is_age_gate<N: Node>(html: &N) -> bool {
+	html.select_first("#_ageGate, .age_gate, .ageGate, form[action*='ageGate']")
+		.is_some()
+}
+
+/// Whether a page is a GDPR/CCPA consent interstitial instead of the
+/// requested series or viewer page.
+pub fn is_consent_page<N: Node>(html: &N) -> bool {
+	html.select_first("#_gdprConsent, .gdpr_consent, .ccpa_consent")
+		.is_some()
+}
+
+/// Find the value of a cookie in a `Cookie` or `Set-Cookie` header.
+pub fn cookie_value<'a>(header: &'a str, name: &str) -> Option<&'a str> {
+	header
+		.split([';', ','])
+		.map(|part: &str| part.trim())
+		.find_map(|part: &str| {
+			let (key, value) = part.split_once('=')?;
+			(key == name).then_some(value)
+		})
+}
+
+/// Fill in manga details scraped from a series list page.
+pub fn parse_manga_details<N: Node>(lang: &Language, html: &N, manga: &mut Manga) {
+	if let Some(title_el) = html.select_first("h1.subj") {
+		if let Some(text) = title_el.text() {
+			manga.title = text;
+		}
+	} else if let Some(title_el) = html.select_first(".subj") {
+		if let Some(text) = title_el.text() {
+			manga.title = text;
+		}
+	}
+
+	if let Some(author_el) = html.select_first(".author_area") {
+		if let Some(text) = author_el.text() {
+			let mut cleaned = text;
+			for label in lang.author_info {
+				cleaned = cleaned.replace(label, "");
+			}
+			parse_credits(lang, &cleaned).apply(manga);
+		}
+	}
+
+	let summary = html
+		.select_first("p.summary")
+		.or_else(|| html.select_first(".summary"))
+		.and_then(|el: N::Element| el.text());
+
+	// Only the series header, as recommendation widgets show other titles' genres
+	let genres = match html.select_first(DETAIL_HEADER_SELECTOR) {
+		Some(header) => collect_genres(lang, header.select(GENRE_SELECTOR)),
+		None => collect_genres(lang, html.select_first(".genre").into_iter().collect()),
+	};
+	genres.apply(manga);
+
+	// Only set cover from og:image if not already set from listing
+	if manga.cover.is_none() {
+		if let Some(meta_el) = html.select_first("meta[property='og:image']") {
+			if let Some(cover_url) = meta_el.attr("content") {
+				manga.cover = Some(cover_url);
+			}
+		}
+	}
+
+	manga.status = if html.select_first(".ico_completed").is_some() {
+		MangaStatus::Completed
+	} else if html.select_first(HIATUS_SELECTOR).is_some() {
+		MangaStatus::Hiatus
+	} else {
+		MangaStatus::Ongoing
+	};
+
+	// Weekly schedule, e.g. "每週六更新"
+	let schedule = if matches!(manga.status, MangaStatus::Ongoing) {
+		html.select_first(".day_info").and_then(|el: N::Element| el.text())
+	} else {
+		None
+	};
+	manga.description = compose_description(summary, schedule);
+
+	let age_marker = html.select_first(AGE_MARKER_SELECTOR).is_some();
+	manga.content_rating = content_rating(&genres.slugs, age_marker);
 }
 
 /// Parse a manga item from listing/genre/search pages.
@@ -55,30 +606,37 @@ pub fn extract_title_no(url: &str) -> Option<String> {
 ///   </div>
 /// </a>
 /// ```
-pub fn parse_manga_item(item: &Element) -> Option<Manga> {
+///
+/// Canvas items link to `/challenge/` or `/canvas/` list pages and get a
+/// prefixed key, see [`make_manga_key`].
+pub fn parse_manga_item<E: Node>(lang: &Language, item: &E) -> Option<Manga> {
 	let href = item.attr("href")?;
 	let title_no = item
 		.attr("data-title-no")
 		.or_else(|| extract_title_no(&href))?;
+	let key = make_manga_key(lang, TitleType::from_url(&href), &title_no);
 
-	// Title: strong.title
+	// Title: strong.title (Originals) or p.subj (Canvas)
 	let title = item
 		.select_first("strong.title")
-		.and_then(|el: Element| el.text())
+		.or_else(|| item.select_first(".subj"))
+		.and_then(|el: E::Element| el.text())
 		.unwrap_or_default();
 
 	if title.is_empty() {
 		return None;
 	}
 
-	// Cover image: div.image_wrap img
+	// Cover image: div.image_wrap img (Originals) or div.img_area img (Canvas)
 	let cover = item
 		.select_first(".image_wrap img")
-		.and_then(|el: Element| el.attr("src"));
+		.or_else(|| item.select_first(".img_area img"))
+		.or_else(|| item.select_first("img"))
+		.and_then(|el: E::Element| el.attr("src"));
 
 	// Author: div.author (may not be present on originals pages)
 	let mut manga = Manga {
-		key: title_no,
+		key,
 		title,
 		cover,
 		url: Some(href),
@@ -88,144 +646,640 @@ pub fn parse_manga_item(item: &Element) -> Option<Manga> {
 
 	if let Some(author_el) = item.select_first(".author") {
 		if let Some(author_text) = author_el.text() {
-			let authors: Vec<String> = author_text
-				.split('/')
-				.map(|s: &str| String::from(s.trim()))
-				.filter(|s: &String| !s.is_empty())
-				.collect();
-			if !authors.is_empty() {
-				manga.authors = Some(authors);
+			parse_credits(lang, &author_text).apply(&mut manga);
+		}
+	}
+
+	// Genre tags (shown on originals pages where author spot has genre)
+	let genres = collect_genres(lang, item.select(GENRE_SELECTOR));
+	genres.apply(&mut manga);
+
+	let age_marker = item.select_first(AGE_MARKER_SELECTOR).is_some();
+	manga.content_rating = content_rating(&genres.slugs, age_marker);
+
+	Some(manga)
+}
+
+/// Selector matching series links on ranking, genre, weekday, search and Canvas pages.
+pub const MANGA_ITEM_SELECTOR: &str = "ul.webtoon_list li a.link, ul.challenge_lst li a, \
+	ul.card_lst li a.card_item, ul.ranking_lst li a";
+
+/// Parse the series of a listing page, and whether it links to a next page.
+pub fn parse_manga_list<N: Node>(lang: &Language, html: &N) -> (Vec<Manga>, bool) {
+	let entries = html
+		.select(MANGA_ITEM_SELECTOR)
+		.iter()
+		.filter_map(|item: &N::Element| parse_manga_item(lang, item))
+		.collect();
+	let has_next_page = html.select_first(".pg_next").is_some();
+	(entries, has_next_page)
+}
+
+/// Parse the images of an episode viewer page.
+///
+/// Images taller than `max_tile_height` (per their `height` attribute) are
+/// returned as several pages, one per tile, see [`TILE_CONTEXT_KEY`]. Each
+/// tile gets its own URL, see [`tile_url`]. Images the viewer lists without
+/// a height are kept whole.
+///
+/// Fails when the page has no images, naming age verification or consent
+/// interstitials served in place of the viewer.
+pub fn parse_viewer_pages<N: Node>(html: &N, max_tile_height: Option<u32>) -> Result<Vec<Page>> {
+	let image_selector = if html.select_first("#_imageList").is_some() {
+		"#_imageList img"
+	} else {
+		".viewer_img img"
+	};
+
+	let mut pages: Vec<Page> = Vec::new();
+	for img in html.select(image_selector) {
+		let Some(url) = img.attr("data-url").or_else(|| img.attr("src")) else {
+			continue;
+		};
+		if url.contains("bg_transparency") || url.contains("warning") || url.contains("loading") {
+			continue;
+		}
+
+		let height: Option<u32> = img.attr("height").and_then(|h: String| h.trim().parse().ok());
+		let tiles = match (height, max_tile_height) {
+			(Some(height), Some(max)) if max > 0 => height.div_ceil(max).max(1),
+			_ => 1,
+		};
+
+		for tile in 0..tiles {
+			let mut context = PageContext::new();
+			context.insert(String::from("Referer"), String::from(BASE_URL_HELPER));
+			let page_url = if tiles > 1 {
+				context.insert(String::from(TILE_CONTEXT_KEY), format!("{tile}/{tiles}"));
+				context.insert(String::from(IMAGE_CONTEXT_KEY), url.clone());
+				tile_url(&url, tile, tiles)
+			} else {
+				url.clone()
+			};
+
+			pages.push(Page {
+				content: PageContent::url_context(&page_url, context),
+				..Default::default()
+			});
+		}
+	}
+
+	if pages.is_empty() {
+		if is_age_gate(html) {
+			bail!("Webtoons showed an age verification page for this chapter");
+		}
+		if is_consent_page(html) {
+			bail!("Webtoons showed a cookie consent page for this chapter");
+		}
+		bail!("No images found for this chapter");
+	}
+	Ok(pages)
+}
+
+/// Page context key of the part of a tall image a page shows, as
+/// `{index}/{count}` with equal-height tiles counted from the top.
+pub const TILE_CONTEXT_KEY: &str = "tile";
+
+/// Page context key of the image a tile is cut from, shared by its tiles.
+pub const IMAGE_CONTEXT_KEY: &str = "image";
+
+/// Fragment marking the tile a page URL shows, e.g. `#tile=1/3`.
+const TILE_FRAGMENT: &str = "#tile=";
+
+/// The URL of one tile of an image, so the app doesn't take the tiles of an
+/// image for the same page when caching by URL.
+pub fn tile_url(url: &str, index: u32, count: u32) -> String {
+	format!("{url}{TILE_FRAGMENT}{index}/{count}")
+}
+
+/// The image URL a tile URL was built from, see [`tile_url`].
+pub fn strip_tile_fragment(url: &str) -> &str {
+	url.find(TILE_FRAGMENT).map(|index: usize| &url[..index]).unwrap_or(url)
+}
+
+/// Read the tile a page shows from its context, as `(index, count)`.
+pub fn page_tile(context: &PageContext) -> Option<(u32, u32)> {
+	let (index, count) = context.get(TILE_CONTEXT_KEY)?.split_once('/')?;
+	let (index, count): (u32, u32) = (index.parse().ok()?, count.parse().ok()?);
+	(index < count).then_some((index, count))
+}
+
+/// Vertical bounds `(top, bottom)` of a tile within the rows `top..bottom`.
+pub fn tile_bounds(top: f32, bottom: f32, index: u32, count: u32) -> (f32, f32) {
+	// Rounded down to whole rows by truncation, as floats have no floor in core
+	let height = (bottom - top) / count.max(1) as f32;
+	let start = top + height * index as f32;
+	let end = if index + 1 >= count { bottom } else { start + height };
+	((start as u32) as f32, (end as u32) as f32)
+}
+
+/// How far a margin row may stray from pure white or black.
+const MARGIN_TOLERANCE: u8 = 16;
+
+/// Find the rows `top..bottom` left after trimming uniform white or black
+/// margins from the top and bottom of a grayscale image.
+///
+/// Images that are entirely blank are kept whole, as spacers between panels
+/// are part of the pacing of a strip.
+pub fn content_rows(luma: &[u8], width: usize, height: usize) -> (usize, usize) {
+	let is_margin = |y: &usize| {
+		let row = &luma[y * width..(y + 1) * width];
+		row.iter().all(|value: &u8| *value >= 255 - MARGIN_TOLERANCE)
+			|| row.iter().all(|value: &u8| *value <= MARGIN_TOLERANCE)
+	};
+	if width == 0 || luma.len() < width * height {
+		return (0, height);
+	}
+	match (0..height).find(|y: &usize| !is_margin(y)) {
+		Some(top) => {
+			let bottom = (0..height).rev().find(|y: &usize| !is_margin(y)).unwrap_or(top);
+			(top, bottom + 1)
+		}
+		None => (0, height),
+	}
+}
+
+/// Decode a JPEG page to grayscale, as `(pixels, width, height)`.
+///
+/// Other formats aren't decoded and return `None`.
+pub fn decode_luma(data: &[u8]) -> Option<(Vec<u8>, usize, usize)> {
+	let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::Luma);
+	let mut decoder = JpegDecoder::new_with_options(data, options);
+	let pixels = decoder.decode().ok()?;
+	let (width, height) = decoder.dimensions()?;
+	Some((pixels, width, height))
+}
+
+/// Rows of a page image left after trimming margins, as
+/// `(top, bottom, height)` in the rows of the decoded image.
+pub type ContentRows = (usize, usize, usize);
+
+/// Measure the content rows of a JPEG page image.
+pub fn image_content_rows(data: &[u8]) -> Option<ContentRows> {
+	let (luma, width, height) = decode_luma(data)?;
+	let (top, bottom) = content_rows(&luma, width, height);
+	Some((top, bottom, height))
+}
+
+/// The rows `(top, bottom)` a page shows of an image `height` rows tall, or
+/// `None` when it shows the whole image.
+///
+/// `content` may be measured on a copy of the image at another quality, as
+/// it's scaled to `height`.
+pub fn page_crop(
+	height: f32,
+	tile: Option<(u32, u32)>,
+	content: Option<ContentRows>,
+) -> Option<(f32, f32)> {
+	let (mut top, mut bottom) = (0.0, height);
+	if let Some((first, last, rows)) = content.filter(|content: &ContentRows| content.2 > 0) {
+		let scale = height / rows as f32;
+		top = first as f32 * scale;
+		bottom = last as f32 * scale;
+	}
+
+	let (index, count) = tile.unwrap_or((0, 1));
+	let (top, bottom) = tile_bounds(top, bottom, index, count);
+	(top > 0.0 || bottom < height).then_some((top, bottom))
+}
+
+/// The content rows of the last image measured, kept so the tiles of a tall
+/// image decode it once between them.
+#[derive(Default)]
+pub struct ContentRowsCache {
+	last: RefCell<Option<(String, Option<ContentRows>)>>,
+}
+
+impl ContentRowsCache {
+	/// The content rows of the image `key`, measured with `measure` when
+	/// they aren't cached.
+	pub fn get(
+		&self,
+		key: &str,
+		measure: impl FnOnce() -> Option<ContentRows>,
+	) -> Option<ContentRows> {
+		let mut last = self.last.borrow_mut();
+		if let Some((cached_key, rows)) = last.as_ref() {
+			if cached_key == key {
+				return *rows;
 			}
 		}
+		let rows = measure();
+		*last = Some((String::from(key), rows));
+		rows
+	}
+}
+
+/// Parse a featured banner of the front page into a manga.
+///
+/// Banners link to a series list page and show the cover either as an image
+/// or as a `background-image` style.
+pub fn parse_banner_item<E: Node>(lang: &Language, item: &E) -> Option<Manga> {
+	let href = item.attr("href")?;
+	let title_no = extract_title_no(&href)?;
+	let key = make_manga_key(lang, TitleType::from_url(&href), &title_no);
+
+	let img = item.select_first("img");
+	let title = item
+		.select_first(".subj, .title, strong")
+		.and_then(|el: E::Element| el.text())
+		.or_else(|| img.as_ref().and_then(|el: &E::Element| el.attr("alt")))
+		.unwrap_or_default();
+	if title.is_empty() {
+		return None;
+	}
+
+	let cover = img
+		.and_then(|el: E::Element| el.attr("data-src").or_else(|| el.attr("src")))
+		.or_else(|| {
+			let style = item.attr("style")?;
+			let start = style.find("url(")? + 4;
+			let end = start + style[start..].find(')')?;
+			Some(String::from(style[start..end].trim_matches(['\'', '"'])))
+		});
+
+	Some(Manga {
+		key,
+		title,
+		cover,
+		url: Some(href),
+		viewer: Viewer::Webtoon,
+		..Default::default()
+	})
+}
+
+/// Elements the site uses to flag titles on break or season hiatus.
+const HIATUS_SELECTOR: &str = ".ico_rest, .ico_hiatus, .ico_break, .day_info .rest";
+
+/// Weekday codes used by the API, Monday first.
+pub const WEEKDAY_CODES: [&str; 7] = [
+	"MONDAY", "TUESDAY", "WEDNESDAY", "THURSDAY", "FRIDAY", "SATURDAY", "SUNDAY",
+];
+
+/// Localized name of an API weekday code such as `SATURDAY`.
+pub fn weekday_name(lang: &Language, code: &str) -> Option<&'static str> {
+	WEEKDAY_CODES
+		.iter()
+		.position(|day: &&str| day.eq_ignore_ascii_case(code))
+		.map(|index: usize| lang.weekdays[index])
+}
+
+/// Listing ids of the weekday pages, Monday first.
+pub const WEEKDAY_LISTINGS: [&str; 7] = [
+	"monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+];
+
+/// Index (Monday = 0) of the current weekday in the edition's timezone.
+pub fn current_weekday(lang: &Language, timestamp: i64) -> usize {
+	let days = (timestamp + lang.utc_offset * 3600).div_euclid(86400);
+	// 1970-01-01 was a Thursday
+	(days + 3).rem_euclid(7) as usize
+}
+
+/// Build a description from a synopsis and the update schedule.
+///
+/// Built afresh on every refresh, so the schedule line never repeats.
+pub fn compose_description(synopsis: Option<String>, schedule: Option<String>) -> Option<String> {
+	let not_empty = |text: &String| !text.trim().is_empty();
+	match (synopsis.filter(not_empty), schedule.filter(not_empty)) {
+		(Some(synopsis), Some(schedule)) => Some(format!("{synopsis}\n\n{}", schedule.trim())),
+		(Some(synopsis), None) => Some(synopsis),
+		(None, schedule) => schedule.map(|schedule: String| String::from(schedule.trim())),
+	}
+}
+
+/// Elements the site uses to flag titles with an age rating.
+pub const AGE_MARKER_SELECTOR: &str = ".ico_adult, .ico_19, .ico_age, .grade_19, .age_grade";
+
+/// Genres behind the site's age gate.
+const NSFW_GENRES: [&str; 1] = ["romance_m"];
+
+/// Genre and label elements on listing items and series pages.
+pub const GENRE_SELECTOR: &str = ".genre, .info .label, .ico_adaptation, .ico_local";
+
+/// Series info header of a series page.
+const DETAIL_HEADER_SELECTOR: &str = ".detail_header .info";
+
+/// Genres of a title, normalized through the language's genre table.
+#[derive(Default)]
+pub struct Genres {
+	/// Display names, in the order found.
+	pub tags: Vec<String>,
+	/// Slugs, including ones missing from the genre table.
+	pub slugs: Vec<String>,
+}
+
+impl Genres {
+	/// Add a genre given as display name or slug; unknown labels are kept as-is.
+	pub fn add(&mut self, lang: &Language, value: &str) {
+		let value = value.trim();
+		if value.is_empty() {
+			return;
+		}
+		match lang.genre_slug(value) {
+			Some(slug) => self.add_slug(lang, slug),
+			None => self.push_tag(value),
+		}
 	}
 
-	// Genre tag (shown on originals pages where author spot has genre)
-	if let Some(genre_el) = item.select_first(".genre") {
-		if let Some(genre_text) = genre_el.text() {
-			manga.tags = Some(aidoku::alloc::vec![genre_text]);
+	/// Add a genre slug or API code (e.g. `ROMANCE_M`).
+	///
+	/// Slugs missing from the genre table only count towards the content rating.
+	pub fn add_slug(&mut self, lang: &Language, slug: &str) {
+		let slug = slug.trim().to_lowercase();
+		if slug.is_empty() {
+			return;
+		}
+		if let Some(name) = lang.genre_name(&slug) {
+			self.push_tag(name);
+		}
+		if !self.slugs.contains(&slug) {
+			self.slugs.push(slug);
 		}
 	}
 
-	Some(manga)
+	fn push_tag(&mut self, tag: &str) {
+		if !self.tags.iter().any(|t: &String| t == tag) {
+			self.tags.push(String::from(tag));
+		}
+	}
+
+	/// Store the tags on a manga, leaving it untouched if there are none.
+	pub fn apply(&self, manga: &mut Manga) {
+		if !self.tags.is_empty() {
+			manga.tags = Some(self.tags.clone());
+		}
+	}
 }
 
+/// Collect genres from the class (`g_{slug}`) and text of genre elements.
+pub fn collect_genres<E: Node>(lang: &Language, elements: Vec<E>) -> Genres {
+	let mut genres = Genres::default();
+	for el in elements {
+		if let Some(slug) = genre_class_slug(&el) {
+			genres.add_slug(lang, &slug);
+		}
+		if let Some(text) = el.text() {
+			genres.add(lang, &text);
+		}
+	}
+	genres
+}
 
+/// Read the genre slug from a `g_{slug}` class, e.g. `<p class="genre g_romance_m">`.
+pub fn genre_class_slug<E: Node>(el: &E) -> Option<String> {
+	el.attr("class")?
+		.split_whitespace()
+		.find_map(|class: &str| class.strip_prefix("g_"))
+		.map(String::from)
+}
+
+/// Derive a content rating from genre slugs and the site's age markers.
+///
+/// Age-gated genres are NSFW; other titles flagged with an age warning, such
+/// as thriller or horror works, are suggestive.
+pub fn content_rating(genre_slugs: &[String], age_marker: bool) -> ContentRating {
+	if genre_slugs
+		.iter()
+		.any(|slug: &String| NSFW_GENRES.contains(&slug.as_str()))
+	{
+		ContentRating::NSFW
+	} else if age_marker {
+		ContentRating::Suggestive
+	} else {
+		ContentRating::Safe
+	}
+}
 
 // --- Mobile API JSON parsing ---
 
 const BASE_URL_HELPER: &str = "https://www.webtoons.com";
 const THUMB_CDN_HELPER: &str = "https://webtoon-phinf.pstatic.net";
 
-/// Extract a JSON string value for a given key from a JSON object substring.
-/// Looks for `"key":"value"` and returns the value.
-fn json_str_value<'a>(json: &'a str, key: &str) -> Option<&'a str> {
-	let search = aidoku::alloc::format!("\"{}\":\"", key);
-	let pos = json.find(&search)?;
-	let start = pos + search.len();
-	let rest = &json[start..];
-	let end = rest.find('"')?;
-	Some(&rest[..end])
-}
-
-/// Extract a JSON number value for a given key from a JSON object substring.
-fn json_num_value(json: &str, key: &str) -> Option<i64> {
-	let search = aidoku::alloc::format!("\"{}\":", key);
-	let pos = json.find(&search)?;
-	let start = pos + search.len();
-	let rest = &json[start..];
-
-	let mut num_str = String::new();
-	for ch in rest.chars() {
-		if ch.is_ascii_digit() || ch == '-' {
-			num_str.push(ch);
-		} else if !num_str.is_empty() {
-			break;
+/// Deserialize a mobile API response and unwrap its `result`.
+///
+/// Fails with the API's own message when `success` is false.
+pub fn parse_api_response<T: DeserializeOwned>(body: &str) -> Result<T> {
+	let response: ApiResponse<T> = serde_json::from_str(body)
+		.map_err(|e| error!("Invalid API response: {e}"))?;
+
+	if !response.success {
+		let message = response
+			.error
+			.and_then(|e: ApiError| e.message)
+			.unwrap_or_else(|| String::from("unknown error"));
+		bail!("Webtoons API error: {message}");
+	}
+
+	response
+		.result
+		.ok_or_else(|| error!("Webtoons API response has no result"))
+}
+
+/// Image quality of covers, thumbnails and pages, picked in settings.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ImageQuality {
+	/// The uploaded file, without any resizing or recompression.
+	Original,
+	/// Whatever the site itself links to.
+	High,
+	/// Recompressed full-size images, for metered connections.
+	DataSaver,
+}
+
+impl ImageQuality {
+	/// Map an `image_quality` setting value; unknown values use [`ImageQuality::High`].
+	pub fn from_setting(value: &str) -> Self {
+		match value {
+			"original" => ImageQuality::Original,
+			"data_saver" => ImageQuality::DataSaver,
+			_ => ImageQuality::High,
 		}
 	}
+}
+
+/// CDN `type` used for data-saver images.
+const DATA_SAVER_TYPE: &str = "q50";
+
+/// Rewrite the `type` parameter of an image CDN URL for `quality`.
+///
+/// Original drops it to get the uploaded file. Data-saver recompresses
+/// full-size images (`q90` or no type), but keeps resized thumbnail types such
+/// as `a160` that are already small. URLs on other hosts are left as-is.
+pub fn apply_image_quality(url: &str, quality: ImageQuality) -> String {
+	let host = THUMB_CDN_HELPER.trim_start_matches("https:");
+	if quality == ImageQuality::High || !url.contains(host) {
+		return String::from(url);
+	}
 
-	num_str.parse::<i64>().ok()
+	let (base, query) = url.split_once('?').unwrap_or((url, ""));
+	let image_type = query
+		.split('&')
+		.find_map(|param: &str| param.strip_prefix("type="));
+	let image_type = match quality {
+		ImageQuality::DataSaver => match image_type {
+			Some(resized) if !resized.starts_with('q') => Some(resized),
+			_ => Some(DATA_SAVER_TYPE),
+		},
+		_ => None,
+	};
+
+	let mut params: Vec<String> = query
+		.split('&')
+		.filter(|param: &&str| !param.is_empty() && !param.starts_with("type="))
+		.map(String::from)
+		.collect();
+	if let Some(image_type) = image_type {
+		params.push(format!("type={image_type}"));
+	}
+	if params.is_empty() {
+		String::from(base)
+	} else {
+		format!("{base}?{}", params.join("&"))
+	}
 }
 
-/// Parse the Webtoons mobile API JSON response into a list of Chapter objects.
+/// Prefix a pstatic path from the mobile API with the image CDN host.
+fn thumbnail_url(path: String) -> String {
+	if path.starts_with("http") {
+		path
+	} else {
+		format!("{THUMB_CDN_HELPER}{path}")
+	}
+}
+
+/// Parse the Webtoons mobile API title info response.
 /// The JSON format is:
 /// ```json
-/// {"result":{"episodeList":[{"episodeNo":1,"episodeTitle":"...","viewerLink":"...","thumbnail":"...","exposureDateMillis":123456},...]},"success":true}
+/// {"result":{"titleInfo":{"title":"...","writingAuthorName":"...","pictureAuthorName":"...","representGenre":"FANTASY","synopsis":"...","restTerminationStatus":"SERIES","thumbnail":"...","ageGradeNotice":false}},"success":true}
 /// ```
-pub fn parse_episodes_json(body: &str) -> Vec<Chapter> {
-	let mut chapters: Vec<Chapter> = Vec::new();
+pub fn parse_title_info_json(body: &str) -> Result<TitleInfo> {
+	let result: TitleInfoResult = parse_api_response(body)?;
+	Ok(result.title_info)
+}
 
-	// Find the episodeList array
-	let list_start = match body.find("\"episodeList\":[") {
-		Some(pos) => pos + 14, // skip past "episodeList":[
-		None => return chapters,
-	};
+/// Fill in manga details from the mobile API title info.
+pub fn apply_title_info(lang: &Language, info: TitleInfo, manga: &mut Manga) {
+	manga.title = info.title;
 
-	let body_from_list = &body[list_start..];
+	let mut credits = Credits::default();
+	if let Some(writers) = info.writing_author_name.as_deref() {
+		credits.add(lang, writers, Role::Writer);
+	}
+	if let Some(illustrators) = info.picture_author_name.as_deref() {
+		credits.add(lang, illustrators, Role::Artist);
+	}
+	// The API has no original work field, and only some titles label it in
+	// the writers; otherwise keep what the listing or series page credited
+	credits.keep_originals(lang, manga.authors.as_deref());
+	credits.apply(manga);
 
-	// Split by each episode object: find each {...} block
-	let mut depth = 0;
-	let mut obj_start: Option<usize> = None;
+	let mut genres = Genres::default();
+	for genre in info.represent_genre.iter().chain(info.genre_list.iter()) {
+		genres.add_slug(lang, genre);
+	}
+	genres.apply(manga);
 
-	for (i, ch) in body_from_list.char_indices() {
-		match ch {
-			'{' => {
-				if depth == 0 {
-					obj_start = Some(i);
-				}
-				depth += 1;
-			}
-			'}' => {
-				depth -= 1;
-				if depth == 0 {
-					if let Some(start) = obj_start {
-						let obj_str = &body_from_list[start..=i];
-						if let Some(chapter) = parse_single_episode(obj_str) {
-							chapters.push(chapter);
-						}
-					}
-					obj_start = None;
-				}
-			}
-			']' if depth == 0 => break,
-			_ => {}
-		}
+	if manga.cover.is_none() {
+		manga.cover = info.thumbnail_vertical.or(info.thumbnail).map(thumbnail_url);
 	}
 
+	manga.status = match info.rest_termination_status.as_deref() {
+		Some("TERMINATION") => MangaStatus::Completed,
+		Some("REST") => MangaStatus::Hiatus,
+		Some("SERIES") => MangaStatus::Ongoing,
+		_ => MangaStatus::Unknown,
+	};
+
+	let days: Vec<&str> = info
+		.weekday
+		.iter()
+		.filter_map(|code: &String| weekday_name(lang, code))
+		.collect();
+	let schedule = (matches!(manga.status, MangaStatus::Ongoing) && !days.is_empty())
+		.then(|| format!("{}{}", lang.update_days, days.join(", ")));
+	manga.description = compose_description(info.synopsis, schedule);
+
+	manga.content_rating = content_rating(&genres.slugs, info.age_grade_notice);
+}
+
+/// Parse the Webtoons mobile API JSON response into a list of Chapter objects.
+/// The JSON format is:
+/// ```json
+/// {"result":{"episodeList":[{"episodeNo":1,"episodeTitle":"...","viewerLink":"...","thumbnail":"...","exposureDateMillis":123456},...]},"success":true}
+/// ```
+///
+/// Paid, Daily Pass and Fast Pass episodes are returned as locked chapters,
+/// with the date they become free appended to the title when known.
+pub fn parse_episodes_json(lang: &Language, title_no: &str, body: &str) -> Result<Vec<Chapter>> {
+	let result: EpisodeListResult = parse_api_response(body)?;
+
+	let mut chapters: Vec<Chapter> = result
+		.episode_list
+		.into_iter()
+		.map(|episode: Episode| parse_single_episode(lang, title_no, episode))
+		.collect();
+
 	// API returns oldest first; reverse to show newest first
 	chapters.reverse();
-	chapters
+	Ok(chapters)
 }
 
-fn parse_single_episode(obj: &str) -> Option<Chapter> {
-	let episode_no = json_num_value(obj, "episodeNo")? as i32;
-	let title = json_str_value(obj, "episodeTitle")
-		.map(|s: &str| String::from(s));
-	let viewer_link = json_str_value(obj, "viewerLink");
-	let thumb_path = json_str_value(obj, "thumbnail");
-	let date_millis = json_num_value(obj, "exposureDateMillis");
+fn parse_single_episode(lang: &Language, title_no: &str, episode: Episode) -> Chapter {
+	let episode_no = episode.episode_no;
+	let locked = episode.is_locked();
 
-	// Unescape URL-encoded paths (the JSON has already-encoded URLs)
-	let viewer_url = viewer_link.map(|link: &str| {
-		aidoku::alloc::format!("{BASE_URL_HELPER}{link}")
-	});
+	let mut title = episode.episode_title;
+	if locked {
+		if let Some(free_millis) = episode.free_exposure_date_millis {
+			let free_date = format_date(free_millis / 1000);
+			let free_on = lang.free_on;
+			title = Some(match title {
+				Some(title) => format!("{title} ({free_on} {free_date})"),
+				None => format!("{free_on} {free_date}"),
+			});
+		}
+	}
 
-	let thumbnail = thumb_path.map(|path: &str| {
-		aidoku::alloc::format!("{THUMB_CDN_HELPER}{path}")
+	// viewerLink is a site-relative path with an already URL-encoded slug
+	let viewer_url = episode.viewer_link.map(|link: String| {
+		if link.starts_with("http") {
+			link
+		} else {
+			format!("{BASE_URL_HELPER}{link}")
+		}
 	});
 
-	let date_uploaded = date_millis.map(|ms: i64| ms / 1000);
+	let thumbnail = episode.thumbnail.map(thumbnail_url);
 
-	let key = viewer_url
-		.clone()
-		.unwrap_or_else(|| aidoku::alloc::format!("{episode_no}"));
+	let date_uploaded = episode.exposure_date_millis.map(|ms: i64| ms / 1000);
 
-	Some(Chapter {
+	let key = make_chapter_key(title_no, episode_no);
+
+	Chapter {
 		key,
 		title,
 		chapter_number: Some(episode_no as f32),
 		date_uploaded,
 		url: viewer_url,
 		thumbnail,
+		locked,
 		..Default::default()
-	})
+	}
+}
+
+/// Format a unix timestamp (seconds, UTC) as `YYYY-MM-DD`.
+pub fn format_date(timestamp: i64) -> String {
+	// Civil-from-days conversion, see http://howardhinnant.github.io/date_algorithms.html
+	let days = timestamp.div_euclid(86400);
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z - era * 146_097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+	format!("{year:04}-{month:02}-{day:02}")
 }
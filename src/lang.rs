@@ -0,0 +1,453 @@
+/// A Webtoons language edition.
+///
+/// Everything that differs between `/zh-hant`, `/en`, `/th`, ... lives here so
+/// that search, listings, details and filters can share one code path.
+pub struct Language {
+	/// Value stored by the `language` setting.
+	pub id: &'static str,
+	/// URL path prefix, e.g. `/zh-hant`.
+	pub path: &'static str,
+	/// Label of the "all genres" option in the genre filter.
+	pub all_genres: &'static str,
+	/// Title of the genre filter.
+	pub genre_title: &'static str,
+	/// Title and option labels (all / any) of the genre match mode filter.
+	pub genre_match_title: &'static str,
+	pub genre_match_labels: [&'static str; 2],
+	/// Title of the sort filter.
+	pub sort_title: &'static str,
+	/// Sort labels, in the same order as `SORT_ORDERS`.
+	pub sort_labels: [&'static str; 3],
+	/// Labels of the author info button inside the author area on detail pages.
+	pub author_info: &'static [&'static str],
+	/// Label put before the date a locked episode becomes free.
+	pub free_on: &'static str,
+	/// Credit labels for writers, illustrators and original work authors.
+	pub writer_roles: &'static [&'static str],
+	pub artist_roles: &'static [&'static str],
+	pub original_roles: &'static [&'static str],
+	/// Label appended to original work credits.
+	pub original_label: &'static str,
+	/// Label put before the update weekdays in descriptions.
+	pub update_days: &'static str,
+	/// Weekday names, Monday first.
+	pub weekdays: [&'static str; 7],
+	/// Offset of the edition's release schedule from UTC, in hours.
+	pub utc_offset: i64,
+	/// Home section titles: today's updates, ranking, new arrivals, completed.
+	pub home_titles: [&'static str; 4],
+	/// Names of the listings in `LISTINGS`, in the same order.
+	pub listings: [&'static str; 10],
+	/// Genre names and their URL slugs.
+	pub genres: &'static [(&'static str, &'static str)],
+}
+
+/// Sort order query values used by genre, ranking and weekday pages.
+pub const SORT_ORDERS: [&str; 3] = ["MANA", "LIKEIT", "UPDATE"];
+
+/// Ids of the rankings, completed and Canvas listings; weekday listings are
+/// named after `Language::weekdays`.
+pub const LISTINGS: [&str; 10] = [
+	"popular",
+	"ranking_trending",
+	"ranking_new",
+	"ranking_male",
+	"ranking_female",
+	"ranking_10s",
+	"ranking_20s",
+	"ranking_30s",
+	"complete",
+	"canvas",
+];
+
+impl Language {
+	/// Find the slug for a genre given either its display name or its slug.
+	pub fn genre_slug(&self, value: &str) -> Option<&'static str> {
+		self.genres
+			.iter()
+			.find(|(name, slug)| *name == value || *slug == value)
+			.map(|(_, slug)| *slug)
+	}
+
+	/// Find the display name for a genre slug.
+	pub fn genre_name(&self, slug: &str) -> Option<&'static str> {
+		self.genres
+			.iter()
+			.find(|(_, s)| *s == slug)
+			.map(|(name, _)| *name)
+	}
+
+	/// Find the name of one of the `LISTINGS`.
+	pub fn listing_name(&self, id: &str) -> Option<&'static str> {
+		LISTINGS
+			.iter()
+			.position(|listing| *listing == id)
+			.map(|index| self.listings[index])
+	}
+
+	/// Map a sort label (or sort order value) to the `sortOrder` query value.
+	pub fn sort_order(&self, value: &str) -> &'static str {
+		self.sort_labels
+			.iter()
+			.zip(SORT_ORDERS.iter())
+			.find(|(label, order)| **label == value || **order == value)
+			.map(|(_, order)| *order)
+			.unwrap_or(SORT_ORDERS[0])
+	}
+}
+
+pub const ZH_HANT: Language = Language {
+	id: "zh-hant",
+	path: "/zh-hant",
+	all_genres: "全部",
+	genre_title: "類型",
+	genre_match_title: "類型比對",
+	genre_match_labels: ["符合全部類型", "符合任一類型"],
+	sort_title: "排序",
+	sort_labels: ["人氣排序", "愛心排序", "最近更新"],
+	author_info: &["作家資訊", "Writer Info"],
+	free_on: "免費開放",
+	writer_roles: &["作家", "編劇", "文", "劇本", "故事"],
+	artist_roles: &["作畫", "繪者", "圖", "繪圖", "插畫"],
+	original_roles: &["原著", "原作"],
+	original_label: "原著",
+	update_days: "更新日：",
+	weekdays: ["週一", "週二", "週三", "週四", "週五", "週六", "週日"],
+	utc_offset: 8,
+	home_titles: ["今日更新", "人氣排行", "新作登場", "完結推薦"],
+	listings: [
+		"人氣排行",
+		"熱門趨勢排行",
+		"新作排行",
+		"男性人氣排行",
+		"女性人氣排行",
+		"10代人氣排行",
+		"20代人氣排行",
+		"30代人氣排行",
+		"完結作品",
+		"挑戰聯盟",
+	],
+	genres: &[
+		("愛情", "romance"),
+		("歐式宮廷", "western_palace"),
+		("影視化", "adaptation"),
+		("校園", "school"),
+		("台灣原創作品", "local"),
+		("奇幻冒險", "fantasy"),
+		("驚悚", "thriller"),
+		("恐怖", "horror"),
+		("武俠", "martial_arts"),
+		("LGBTQ+", "bl_gl"),
+		("大人系", "romance_m"),
+		("劇情", "drama"),
+		("動作", "action"),
+		("生活/日常", "slice_of_life"),
+		("搞笑", "comedy"),
+		("穿越/轉生", "time_slip"),
+		("現代/職場", "city_office"),
+		("懸疑推理", "mystery"),
+		("療癒/萌系", "heartwarming"),
+		("少年", "shonen"),
+		("古代宮廷", "eastern_palace"),
+		("小說", "web_novel"),
+	],
+};
+
+pub const EN: Language = Language {
+	id: "en",
+	path: "/en",
+	all_genres: "All",
+	genre_title: "Genre",
+	genre_match_title: "Genre match",
+	genre_match_labels: ["All selected genres", "Any selected genre"],
+	sort_title: "Sort",
+	sort_labels: ["Popularity", "Likes", "Date"],
+	author_info: &["author info", "Writer Info"],
+	free_on: "Free on",
+	writer_roles: &["Writer", "Story", "Author"],
+	artist_roles: &["Artist", "Art", "Illustrator"],
+	original_roles: &["Original Author", "Original Work", "Original Story", "Adapted from"],
+	original_label: "Original",
+	update_days: "Updates every ",
+	weekdays: ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+	utc_offset: -5,
+	home_titles: ["Today's Updates", "Top Ranking", "New Arrivals", "Completed Picks"],
+	listings: [
+		"Popular",
+		"Trending",
+		"New Releases",
+		"Popular with Men",
+		"Popular with Women",
+		"Popular with Teens",
+		"Popular in Their 20s",
+		"Popular in Their 30s",
+		"Completed",
+		"Canvas",
+	],
+	genres: &[
+		("Romance", "romance"),
+		("Fantasy", "fantasy"),
+		("Drama", "drama"),
+		("Action", "action"),
+		("Comedy", "comedy"),
+		("Slice of life", "slice_of_life"),
+		("Superhero", "super_hero"),
+		("Sci-fi", "sf"),
+		("Thriller", "thriller"),
+		("Supernatural", "supernatural"),
+		("Mystery", "mystery"),
+		("Sports", "sports"),
+		("Historical", "historical"),
+		("Heartwarming", "heartwarming"),
+		("Horror", "horror"),
+		("Informative", "tiptoon"),
+	],
+};
+
+pub const TH: Language = Language {
+	id: "th",
+	path: "/th",
+	all_genres: "ทั้งหมด",
+	genre_title: "ประเภท",
+	genre_match_title: "การจับคู่ประเภท",
+	genre_match_labels: ["ตรงทุกประเภท", "ตรงประเภทใดก็ได้"],
+	sort_title: "เรียงตาม",
+	sort_labels: ["ยอดนิยม", "ถูกใจ", "อัปเดตล่าสุด"],
+	author_info: &["ข้อมูลนักเขียน"],
+	free_on: "อ่านฟรี",
+	writer_roles: &["เรื่อง", "นักเขียน"],
+	artist_roles: &["ภาพ", "นักวาด"],
+	original_roles: &["ต้นฉบับ", "เรื่องต้นฉบับ"],
+	original_label: "ต้นฉบับ",
+	update_days: "อัปเดตทุกวัน",
+	weekdays: ["จันทร์", "อังคาร", "พุธ", "พฤหัสบดี", "ศุกร์", "เสาร์", "อาทิตย์"],
+	utc_offset: 7,
+	home_titles: ["อัปเดตวันนี้", "อันดับยอดนิยม", "เรื่องใหม่", "จบแล้วน่าอ่าน"],
+	listings: [
+		"ยอดนิยม",
+		"มาแรง",
+		"เรื่องใหม่มาแรง",
+		"ยอดนิยมในผู้ชาย",
+		"ยอดนิยมในผู้หญิง",
+		"ยอดนิยมวัย 10+",
+		"ยอดนิยมวัย 20+",
+		"ยอดนิยมวัย 30+",
+		"จบแล้ว",
+		"Canvas",
+	],
+	genres: &[
+		("โรแมนซ์", "romance"),
+		("แฟนตาซี", "fantasy"),
+		("ดราม่า", "drama"),
+		("แอคชั่น", "action"),
+		("ตลก", "comedy"),
+		("ชีวิตประจำวัน", "slice_of_life"),
+		("ซูเปอร์ฮีโร่", "super_hero"),
+		("ไซไฟ", "sf"),
+		("ระทึกขวัญ", "thriller"),
+		("สยองขวัญ", "horror"),
+		("สืบสวน", "mystery"),
+		("กีฬา", "sports"),
+		("ย้อนยุค", "historical"),
+	],
+};
+
+pub const ID: Language = Language {
+	id: "id",
+	path: "/id",
+	all_genres: "Semua",
+	genre_title: "Genre",
+	genre_match_title: "Pencocokan genre",
+	genre_match_labels: ["Semua genre terpilih", "Salah satu genre terpilih"],
+	sort_title: "Urutkan",
+	sort_labels: ["Populer", "Suka", "Terbaru"],
+	author_info: &["info kreator"],
+	free_on: "Gratis pada",
+	writer_roles: &["Penulis", "Cerita"],
+	artist_roles: &["Ilustrator", "Gambar"],
+	original_roles: &["Karya Asli", "Penulis Asli"],
+	original_label: "Karya Asli",
+	update_days: "Update setiap ",
+	weekdays: ["Senin", "Selasa", "Rabu", "Kamis", "Jumat", "Sabtu", "Minggu"],
+	utc_offset: 7,
+	home_titles: ["Update Hari Ini", "Peringkat Teratas", "Judul Baru", "Pilihan Tamat"],
+	listings: [
+		"Populer",
+		"Trending",
+		"Judul Baru",
+		"Populer di Pria",
+		"Populer di Wanita",
+		"Populer Usia 10-an",
+		"Populer Usia 20-an",
+		"Populer Usia 30-an",
+		"Tamat",
+		"Kanvas",
+	],
+	genres: &[
+		("Romantis", "romance"),
+		("Fantasi", "fantasy"),
+		("Drama", "drama"),
+		("Aksi", "action"),
+		("Komedi", "comedy"),
+		("Slice of Life", "slice_of_life"),
+		("Superhero", "super_hero"),
+		("Sci-fi", "sf"),
+		("Thriller", "thriller"),
+		("Horor", "horror"),
+		("Supernatural", "supernatural"),
+		("Misteri", "mystery"),
+		("Sports", "sports"),
+	],
+};
+
+pub const ES: Language = Language {
+	id: "es",
+	path: "/es",
+	all_genres: "Todos",
+	genre_title: "Género",
+	genre_match_title: "Coincidencia de géneros",
+	genre_match_labels: ["Todos los géneros", "Cualquier género"],
+	sort_title: "Ordenar",
+	sort_labels: ["Popularidad", "Me gusta", "Actualización"],
+	author_info: &["info del autor"],
+	free_on: "Gratis el",
+	writer_roles: &["Guion", "Escritor", "Historia"],
+	artist_roles: &["Arte", "Dibujo", "Ilustrador"],
+	original_roles: &["Obra original", "Autor original"],
+	original_label: "Obra original",
+	update_days: "Se actualiza cada ",
+	weekdays: ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+	utc_offset: -5,
+	home_titles: ["Actualizaciones de hoy", "Ranking", "Novedades", "Completos"],
+	listings: [
+		"Popular",
+		"Tendencias",
+		"Novedades",
+		"Popular entre hombres",
+		"Popular entre mujeres",
+		"Popular entre adolescentes",
+		"Popular entre 20 y 29 años",
+		"Popular entre 30 y 39 años",
+		"Completos",
+		"Canvas",
+	],
+	genres: &[
+		("Romance", "romance"),
+		("Fantasía", "fantasy"),
+		("Drama", "drama"),
+		("Acción", "action"),
+		("Comedia", "comedy"),
+		("Recuentos de la vida", "slice_of_life"),
+		("Superhéroes", "super_hero"),
+		("Ciencia ficción", "sf"),
+		("Suspenso", "thriller"),
+		("Terror", "horror"),
+		("Sobrenatural", "supernatural"),
+		("Misterio", "mystery"),
+	],
+};
+
+pub const FR: Language = Language {
+	id: "fr",
+	path: "/fr",
+	all_genres: "Tous",
+	genre_title: "Genre",
+	genre_match_title: "Correspondance des genres",
+	genre_match_labels: ["Tous les genres", "N'importe quel genre"],
+	sort_title: "Trier",
+	sort_labels: ["Popularité", "J'aime", "Mise à jour"],
+	author_info: &["info auteur"],
+	free_on: "Gratuit le",
+	writer_roles: &["Scénario", "Auteur", "Histoire"],
+	artist_roles: &["Dessin", "Illustrateur", "Art"],
+	original_roles: &["Œuvre originale", "Auteur original"],
+	original_label: "Œuvre originale",
+	update_days: "Mis à jour chaque ",
+	weekdays: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+	utc_offset: 1,
+	home_titles: ["Mises à jour du jour", "Classement", "Nouveautés", "Séries terminées"],
+	listings: [
+		"Populaire",
+		"Tendances",
+		"Nouveautés",
+		"Populaire chez les hommes",
+		"Populaire chez les femmes",
+		"Populaire chez les ados",
+		"Populaire chez les 20-29 ans",
+		"Populaire chez les 30-39 ans",
+		"Séries terminées",
+		"Canvas",
+	],
+	genres: &[
+		("Romance", "romance"),
+		("Fantastique", "fantasy"),
+		("Drame", "drama"),
+		("Action", "action"),
+		("Comédie", "comedy"),
+		("Tranche de vie", "slice_of_life"),
+		("Super-héros", "super_hero"),
+		("Science-fiction", "sf"),
+		("Thriller", "thriller"),
+		("Horreur", "horror"),
+		("Surnaturel", "supernatural"),
+		("Mystère", "mystery"),
+	],
+};
+
+pub const DE: Language = Language {
+	id: "de",
+	path: "/de",
+	all_genres: "Alle",
+	genre_title: "Genre",
+	genre_match_title: "Genre-Abgleich",
+	genre_match_labels: ["Alle Genres", "Beliebiges Genre"],
+	sort_title: "Sortieren",
+	sort_labels: ["Beliebtheit", "Likes", "Aktualisiert"],
+	author_info: &["Autor-Info"],
+	free_on: "Kostenlos ab",
+	writer_roles: &["Autor", "Story", "Text"],
+	artist_roles: &["Zeichner", "Zeichnungen", "Illustrator"],
+	original_roles: &["Originalwerk", "Originalautor"],
+	original_label: "Originalwerk",
+	update_days: "Neue Folgen jeden ",
+	weekdays: ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+	utc_offset: 1,
+	home_titles: ["Heute neu", "Ranking", "Neuerscheinungen", "Abgeschlossen"],
+	listings: [
+		"Beliebt",
+		"Im Trend",
+		"Neuerscheinungen",
+		"Beliebt bei Männern",
+		"Beliebt bei Frauen",
+		"Beliebt bei Teens",
+		"Beliebt in den 20ern",
+		"Beliebt in den 30ern",
+		"Abgeschlossen",
+		"Canvas",
+	],
+	genres: &[
+		("Romance", "romance"),
+		("Fantasy", "fantasy"),
+		("Drama", "drama"),
+		("Action", "action"),
+		("Comedy", "comedy"),
+		("Slice of Life", "slice_of_life"),
+		("Superhelden", "super_hero"),
+		("Sci-Fi", "sf"),
+		("Thriller", "thriller"),
+		("Horror", "horror"),
+		("Mystery", "mystery"),
+	],
+};
+
+/// All supported language editions, in the order of the settings menu.
+pub static LANGUAGES: [&Language; 7] = [&ZH_HANT, &EN, &TH, &ID, &ES, &FR, &DE];
+
+/// Look up a language by its setting value, falling back to Traditional Chinese.
+pub fn language_by_id(id: &str) -> &'static Language {
+	LANGUAGES
+		.iter()
+		.find(|lang| lang.id == id)
+		.copied()
+		.unwrap_or(&ZH_HANT)
+}
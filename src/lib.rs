@@ -1,50 +1,179 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 use aidoku::{
 	alloc::{String, Vec},
+	imports::canvas::{Canvas, ImageRef, Rect},
 	imports::net::Request,
 	prelude::*,
-	Chapter, ContentRating, DeepLinkHandler, DeepLinkResult, FilterValue,
-	ImageRequestProvider, Listing, ListingProvider, Manga, MangaPageResult, MangaStatus,
-	Page, PageContent, PageContext, Result, Source, Viewer,
+	imports::std::current_date,
+	Chapter, DeepLinkHandler, DeepLinkResult, DynamicFilters, DynamicListings, Filter,
+	FilterValue, HashMap, Home, HomeComponent, HomeComponentValue, HomeLayout,
+	ImageRequestProvider, ImageResponse, Link, Listing, ListingProvider, Manga, MangaPageResult,
+	MigrationHandler, MultiSelectFilter, NotificationHandler, Page, PageContext,
+	PageImageProcessor, Result, SelectFilter, Source, WebLoginHandler,
 };
 
+mod client;
 mod helper;
+mod lang;
+mod models;
+mod net;
+mod node;
+mod settings;
+mod transport;
+#[cfg(test)]
+mod tests;
+use client::{Client, ListingCache};
 use helper::*;
+use lang::{Language, LISTINGS, SORT_ORDERS};
+use net::AidokuTransport;
 
 const BASE_URL: &str = "https://www.webtoons.com";
-const LANG_PATH: &str = "/zh-hant";
-const USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
 
-/// Webtoons mobile API base URL for fetching all episodes in one request.
-const MOBILE_API: &str = "https://m.webtoons.com/api/v1/webtoon";
+/// Prefix of per-genre ranking listing ids, e.g. `ranking_genre:fantasy`.
+const GENRE_RANKING_PREFIX: &str = "ranking_genre:";
 
-struct WebtoonSource;
+/// Prefix of genre listing ids opened from deep links, e.g. `genre:fantasy`.
+const GENRE_LISTING_PREFIX: &str = "genre:";
 
-/// Helper: fetch a page and parse manga items.
-fn fetch_manga_list(url: &str) -> Result<(Vec<Manga>, bool)> {
-	let html = Request::get(url)?
-		.header("Referer", BASE_URL)
-		.header("User-Agent", USER_AGENT)
-		.html()?;
+/// Number of ranking listings at the start of `LISTINGS`.
+const RANKING_LISTING_COUNT: usize = 8;
 
-	let mut entries: Vec<Manga> = Vec::new();
+/// Helper: the name of a listing in the edition's language.
+fn listing_name(lang: &Language, id: &str) -> Option<String> {
+	if let Some(name) = lang.listing_name(id) {
+		return Some(String::from(name));
+	}
+	let index = WEEKDAY_LISTINGS.iter().position(|day: &&str| *day == id)?;
+	Some(String::from(lang.weekdays[index]))
+}
+
+/// Helper: map a genre, ranking, weekday, completed or Canvas page URL to
+/// one of the source's listings, as `(id, name)`.
+///
+/// Listings show the selected edition, so pages of other editions aren't
+/// mapped.
+fn deep_link_listing(lang: &Language, url: &str) -> Option<(String, String)> {
+	let segments = url_path_segments(url);
+	// Drop the language prefix, e.g. `zh-hant` or `en`
+	let segments: &[&str] = match url_language(url) {
+		Some(edition) if edition.id != lang.id => return None,
+		Some(_) => &segments[1..],
+		None => &segments,
+	};
+
+	let id = match segments {
+		["genres", slug, ..] => {
+			let name = lang.genre_name(slug).unwrap_or(*slug);
+			return Some((format!("{GENRE_LISTING_PREFIX}{slug}"), String::from(name)));
+		}
+		["ranking", "trending", ..] => "ranking_trending",
+		["ranking", "new", ..] => "ranking_new",
+		["ranking", ..] => "popular",
+		["originals", "complete", ..] => "complete",
+		["originals", day, ..] | ["dailySchedule", day, ..] => weekday_listing(day)?,
+		["originals"] | ["dailySchedule"] => {
+			weekday_listing(&extract_query_param(url, "weekday")?)?
+		}
+		["canvas", ..] | ["challenge", ..] => "canvas",
+		_ => return None,
+	};
+	Some((String::from(id), listing_name(lang, id)?))
+}
+
+/// Helper: the weekday listing id for a day given as `monday` or `MONDAY`.
+fn weekday_listing(day: &str) -> Option<&'static str> {
+	WEEKDAY_LISTINGS
+		.iter()
+		.find(|listing: &&&str| listing.eq_ignore_ascii_case(day))
+		.copied()
+}
 
-	if let Some(list) = html.select("ul.webtoon_list li a.link") {
-		for item in list {
-			if let Some(manga) = parse_manga_item(&item) {
-				entries.push(manga);
+/// Helper: build the URL of a ranking listing page.
+///
+/// Segmented rankings share the ranking page and differ by `target`; genre
+/// rankings use ids of the form `ranking_genre:{slug}`, see
+/// [`genre_ranking_listings`].
+fn ranking_url(lang: &Language, listing_id: &str, page: i32) -> Option<String> {
+	let lang_path = lang.path;
+	let target = match listing_id {
+		"popular" => "",
+		"ranking_trending" => {
+			return Some(format!("{BASE_URL}{lang_path}/ranking/trending?page={page}"));
+		}
+		"ranking_new" => {
+			return Some(format!("{BASE_URL}{lang_path}/ranking/new?page={page}"));
+		}
+		"ranking_male" => "&target=MALE",
+		"ranking_female" => "&target=FEMALE",
+		"ranking_10s" => "&target=AGE_10",
+		"ranking_20s" => "&target=AGE_20",
+		"ranking_30s" => "&target=AGE_30",
+		_ => {
+			let genre = listing_id
+				.strip_prefix(GENRE_RANKING_PREFIX)
+				.filter(|slug: &&str| !slug.is_empty())?;
+			return Some(format!(
+				"{BASE_URL}{lang_path}/ranking?sortOrder=MANA&genre={}&page={page}",
+				genre.to_uppercase()
+			));
+		}
+	};
+	Some(format!(
+		"{BASE_URL}{lang_path}/ranking?sortOrder=MANA{target}&page={page}"
+	))
+}
+
+struct WebtoonSource {
+	/// Listings merged client-side, kept while the app pages through them.
+	listings: ListingCache,
+	/// Trim bounds of the last tiled image, shared by its tiles.
+	content_rows: ContentRowsCache,
+}
+
+/// Weekday and completed pages, which together list every Originals title.
+const SCHEDULE_PAGES: [&str; 8] = [
+	"monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday", "complete",
+];
+
+/// Filter value of the genre match mode that unions the included genres.
+const GENRE_MATCH_ANY: &str = "any";
+
+/// Helper: the genres the site offers.
+///
+/// With `refresh`, the genre navigation is fetched and cached; otherwise, or
+/// when that fails, the cached list is used, then the built-in table.
+fn available_genres(client: &Client<AidokuTransport>, refresh: bool) -> Vec<GenreEntry> {
+	let lang = client.lang;
+	if refresh {
+		let url = format!("{BASE_URL}{}/genres", lang.path);
+		if let Ok(html) = client.get_html(&url) {
+			let genres = parse_genre_nav(&html);
+			if !genres.is_empty() {
+				settings::set_cached_genres(lang, &genres);
+				return genres;
 			}
 		}
 	}
+	settings::get_cached_genres(lang).unwrap_or_else(|| static_genres(lang))
+}
 
-	let has_next_page = html.select_first(".pg_next").is_some();
-	Ok((entries, has_next_page))
+/// Helper: a client for the selected language, sending requests through the app.
+fn client() -> Client<AidokuTransport> {
+	client_for(settings::get_language())
+}
+
+/// Helper: a client for a language edition, such as the one a manga key names.
+fn client_for(lang: &'static Language) -> Client<AidokuTransport> {
+	Client::new(AidokuTransport { lang }, lang)
 }
 
 impl Source for WebtoonSource {
 	fn new() -> Self {
-		Self
+		Self {
+			listings: ListingCache::default(),
+			content_rows: ContentRowsCache::default(),
+		}
 	}
 
 	fn get_search_manga_list(
@@ -53,230 +182,260 @@ impl Source for WebtoonSource {
 		page: i32,
 		filters: Vec<FilterValue>,
 	) -> Result<MangaPageResult> {
-		if let Some(keyword) = query {
-			let url = format!("{BASE_URL}{LANG_PATH}/search?keyword={keyword}");
-			let (entries, _) = fetch_manga_list(&url)?;
-			return Ok(MangaPageResult {
-				entries,
-				has_next_page: false,
-			});
-		}
-
-		let mut genre_slug = "romance";
-		let mut sort_order = "MANA";
-
-		for filter in filters {
-			match filter {
-				FilterValue::Select { id, value } => {
-					if id == "genre" {
-						genre_slug = genre_name_to_slug(&value);
-					} else if id == "sort" {
-						sort_order = match value.as_str() {
-							"愛心排序" => "LIKEIT",
-							"最近更新" => "UPDATE",
-							_ => "MANA",
-						};
-					}
-				}
-				_ => {}
-			}
-		}
-
-		let url = format!(
-			"{BASE_URL}{LANG_PATH}/genres/{genre_slug}?sortOrder={sort_order}&page={page}"
-		);
-
-		let (entries, has_next_page) = fetch_manga_list(&url)?;
-
-		Ok(MangaPageResult {
-			entries,
-			has_next_page,
-		})
+		let client = client();
+		let genres = available_genres(&client, false);
+		client.search(&self.listings, &genres, query, page, filters)
 	}
 
 	fn get_manga_update(
 		&self,
-		mut manga: Manga,
+		manga: Manga,
 		needs_details: bool,
 		needs_chapters: bool,
 	) -> Result<Manga> {
-		let title_no = manga.key.clone();
-
-		if needs_details {
-			let detail_url = if let Some(ref url) = manga.url {
-				url.clone()
-			} else {
-				format!("{BASE_URL}{LANG_PATH}/originals/a/list?title_no={title_no}")
-			};
-
-			let html = Request::get(&detail_url)?
-				.header("Referer", BASE_URL)
-				.header("User-Agent", USER_AGENT)
-				.html()?;
-
-			if let Some(title_el) = html.select_first("h1.subj") {
-				if let Some(text) = title_el.text() {
-					manga.title = text;
-				}
-			} else if let Some(title_el) = html.select_first(".subj") {
-				if let Some(text) = title_el.text() {
-					manga.title = text;
-				}
-			}
-
-			if let Some(author_el) = html.select_first(".author_area") {
-				if let Some(text) = author_el.text() {
-					let cleaned = text
-						.replace("Writer Info", "")
-						.replace("作家資訊", "");
-					let authors: Vec<String> = cleaned
-						.split(',')
-						.flat_map(|s: &str| s.split('/'))
-						.map(|s: &str| String::from(s.trim()))
-						.filter(|s: &String| !s.is_empty())
-						.collect();
-					if !authors.is_empty() {
-						manga.authors = Some(authors);
-					}
-				}
-			}
+		// Series stay in their own edition after the language setting changes
+		let (lang, _, _) = parse_manga_key(&manga.key);
+		client_for(lang).manga_update(manga, needs_details, needs_chapters)
+	}
 
-			if let Some(desc_el) = html.select_first("p.summary") {
-				if let Some(text) = desc_el.text() {
-					manga.description = Some(text);
-				}
-			} else if let Some(desc_el) = html.select_first(".summary") {
-				if let Some(text) = desc_el.text() {
-					manga.description = Some(text);
-				}
-			}
+	fn get_page_list(&self, manga: Manga, chapter: Chapter) -> Result<Vec<Page>> {
+		let (lang, _, _) = parse_manga_key(&manga.key);
+		client_for(lang).page_list(&manga, &chapter, settings::get_tile_height())
+	}
+}
 
-			if let Some(genre_el) = html.select_first(".genre") {
-				if let Some(text) = genre_el.text() {
-					manga.tags = Some(aidoku::alloc::vec![text]);
-				}
+impl ListingProvider for WebtoonSource {
+	fn get_manga_list(&self, listing: Listing, page: i32) -> Result<MangaPageResult> {
+		let client = client();
+		let lang = client.lang;
+		let lang_path = lang.path;
+		let url = match listing.id.as_str() {
+			"canvas" => format!(
+				"{BASE_URL}{lang_path}/canvas/list?genreTab=ALL&sortOrder=MANA&page={page}"
+			),
+			day if SCHEDULE_PAGES.contains(&day) => {
+				// The site lists a whole day (or every completed title) on one
+				// page, so it's paged here
+				let sort_order = settings::get_listing_sort();
+				let url = format!(
+					"{BASE_URL}{lang_path}/originals/{day}?sortOrder={sort_order}"
+				);
+				return self.listings.page(&url, page, || {
+					client.fetch_manga_list(&url).map(|(entries, _)| entries)
+				});
 			}
-
-			// Only set cover from og:image if not already set from listing
-			if manga.cover.is_none() {
-				if let Some(meta_el) = html.select_first("meta[property='og:image']") {
-					if let Some(cover_url) = meta_el.attr("content") {
-						manga.cover = Some(cover_url);
-					}
-				}
+			id if id.starts_with(GENRE_LISTING_PREFIX) => {
+				let genre_slug = &id[GENRE_LISTING_PREFIX.len()..];
+				let sort_order = settings::get_listing_sort();
+				format!(
+					"{BASE_URL}{lang_path}/genres/{genre_slug}?sortOrder={sort_order}&page={page}"
+				)
 			}
+			id => match ranking_url(lang, id, page) {
+				Some(url) => url,
+				None => bail!("Unknown listing: {}", listing.id),
+			},
+		};
 
-			let is_completed = html.select_first(".ico_completed").is_some();
-			manga.status = if is_completed {
-				MangaStatus::Completed
-			} else {
-				MangaStatus::Ongoing
-			};
-
-			manga.content_rating = ContentRating::Safe;
-			manga.viewer = Viewer::Webtoon;
-		}
-
-		if needs_chapters {
-			// Use Webtoons mobile API to get ALL chapters in one request.
-			// Endpoint: m.webtoons.com/api/v1/webtoon/{titleId}/episodes?pageSize=99999
-			let api_url = format!(
-				"{MOBILE_API}/{title_no}/episodes?pageSize=99999"
-			);
+		let (entries, has_next_page) = client.fetch_manga_list(&url)?;
 
-			let body = Request::get(&api_url)?
-				.header("Referer", BASE_URL)
-				.header("User-Agent", USER_AGENT)
-				.string()?;
+		Ok(MangaPageResult {
+			entries,
+			has_next_page,
+		})
+	}
+}
 
-			let chapters = parse_episodes_json(&body);
+/// Helper: a ranking listing per genre the site offers.
+fn genre_ranking_listings(lang: &Language, genres: &[GenreEntry]) -> Vec<Listing> {
+	let ranking = lang.listing_name("popular").unwrap_or_default();
+	genres
+		.iter()
+		.map(|(name, slug)| Listing {
+			id: format!("{GENRE_RANKING_PREFIX}{slug}"),
+			name: format!("{ranking} · {name}"),
+			..Default::default()
+		})
+		.collect()
+}
 
-			manga.chapters = Some(chapters);
-		}
+impl DynamicListings for WebtoonSource {
+	fn get_dynamic_listings(&self) -> Result<Vec<Listing>> {
+		let client = client();
+		let lang = client.lang;
+		let listing = |id: &&str| {
+			Some(Listing {
+				id: String::from(*id),
+				name: listing_name(lang, id)?,
+				..Default::default()
+			})
+		};
 
-		Ok(manga)
+		// Rankings, genre rankings, then the weekday, completed and Canvas catalogs
+		let (rankings, catalogs) = LISTINGS.split_at(RANKING_LISTING_COUNT);
+		let mut listings: Vec<Listing> = rankings.iter().filter_map(listing).collect();
+		listings.extend(genre_ranking_listings(lang, &available_genres(&client, false)));
+		listings.extend(WEEKDAY_LISTINGS.iter().chain(catalogs.iter()).filter_map(listing));
+		Ok(listings)
 	}
+}
 
-	fn get_page_list(&self, _manga: Manga, chapter: Chapter) -> Result<Vec<Page>> {
-		let viewer_url = if let Some(ref url) = chapter.url {
-			url.clone()
+/// Selectors of the front page sections used by the home layout.
+const HOME_BANNER_SELECTOR: &str = "#_mainBanner li a, .main_banner li a, .main_banner_area a";
+const HOME_NEW_SELECTOR: &str = "#_newTitleList li a, .new_title_area li a, .new_list li a";
+
+/// Number of entries shown per list section on the home page.
+const HOME_SECTION_SIZE: usize = 10;
+
+/// Helper: a home section linking to one of the source's listings.
+fn listing_section(
+	title: &str,
+	listing_id: &str,
+	ranking: bool,
+	entries: Vec<Manga>,
+) -> Option<HomeComponent> {
+	if entries.is_empty() {
+		return None;
+	}
+	let listing = Listing {
+		id: listing_id.into(),
+		name: title.into(),
+		..Default::default()
+	};
+	let entries: Vec<Link> = entries
+		.into_iter()
+		.take(HOME_SECTION_SIZE)
+		.map(|manga: Manga| manga.into())
+		.collect();
+	Some(HomeComponent {
+		title: Some(title.into()),
+		subtitle: None,
+		value: if ranking {
+			HomeComponentValue::MangaList {
+				ranking: true,
+				page_size: None,
+				entries,
+				listing: Some(listing),
+			}
 		} else {
-			chapter.key.clone()
-		};
+			HomeComponentValue::Scroller {
+				entries,
+				listing: Some(listing),
+			}
+		},
+	})
+}
 
-		let html = Request::get(&viewer_url)?
-			.header("Referer", BASE_URL)
-			.header("User-Agent", USER_AGENT)
-			.html()?;
+impl Home for WebtoonSource {
+	fn get_home(&self) -> Result<HomeLayout> {
+		let client = client();
+		let lang = client.lang;
+		let lang_path = lang.path;
+		let [today_title, ranking_title, new_title, completed_title] = lang.home_titles;
 
-		let mut pages: Vec<Page> = Vec::new();
+		let mut components: Vec<HomeComponent> = Vec::new();
 
-		let image_selector = if html.select_first("#_imageList").is_some() {
-			"#_imageList img"
-		} else {
-			".viewer_img img"
+		// A failing section shouldn't take the whole home page down
+		let listing_entries = |id: &str| -> Vec<Manga> {
+			let listing = Listing {
+				id: id.into(),
+				..Default::default()
+			};
+			self.get_manga_list(listing, 1)
+				.map(|result: MangaPageResult| result.entries)
+				.unwrap_or_default()
 		};
 
-		if let Some(images) = html.select(image_selector) {
-			for img in images {
-				let img_url: Option<String> = img
-					.attr("data-url")
-					.or_else(|| img.attr("src"));
-
-				if let Some(url) = img_url {
-					if url.contains("bg_transparency")
-						|| url.contains("warning")
-						|| url.contains("loading")
-					{
-						continue;
-					}
-
-					let mut context = PageContext::new();
-					context.insert(
-						String::from("Referer"),
-						String::from("https://www.webtoons.com"),
-					);
-
-					pages.push(Page {
-						content: PageContent::url_context(&url, context),
-						..Default::default()
-					});
-				}
-			}
+		// Featured banners and new arrivals come from the front page
+		let front = client.get_html(&format!("{BASE_URL}{lang_path}/")).ok();
+		let banners: Vec<Manga> = front
+			.as_ref()
+			.and_then(|html| html.select(HOME_BANNER_SELECTOR))
+			.into_iter()
+			.flatten()
+			.filter_map(|item| parse_banner_item(lang, &item))
+			.collect();
+		if !banners.is_empty() {
+			components.push(HomeComponent {
+				title: None,
+				subtitle: None,
+				value: HomeComponentValue::BigScroller {
+					entries: banners,
+					auto_scroll_interval: Some(8.0),
+				},
+			});
+		}
+		let mut new_arrivals: Vec<Manga> = front
+			.as_ref()
+			.and_then(|html| html.select(HOME_NEW_SELECTOR))
+			.into_iter()
+			.flatten()
+			.filter_map(|item| parse_manga_item(lang, &item))
+			.collect();
+		if new_arrivals.is_empty() {
+			new_arrivals = listing_entries("ranking_new");
 		}
 
-		Ok(pages)
+		let today = WEEKDAY_LISTINGS[current_weekday(lang, current_date())];
+		components.extend(listing_section(today_title, today, false, listing_entries(today)));
+		components.extend(listing_section(
+			ranking_title,
+			"popular",
+			true,
+			listing_entries("popular"),
+		));
+		components.extend(listing_section(new_title, "ranking_new", false, new_arrivals));
+		components.extend(listing_section(
+			completed_title,
+			"complete",
+			false,
+			listing_entries("complete"),
+		));
+
+		Ok(HomeLayout { components })
 	}
 }
 
-impl ListingProvider for WebtoonSource {
-	fn get_manga_list(&self, listing: Listing, page: i32) -> Result<MangaPageResult> {
-		let url = match listing.id.as_str() {
-			"popular" => format!(
-				"{BASE_URL}{LANG_PATH}/ranking?sortOrder=MANA&page={page}"
-			),
-			day @ ("monday" | "tuesday" | "wednesday" | "thursday"
-				| "friday" | "saturday" | "sunday" | "complete") =>
-			{
-				if page > 1 {
-					return Ok(MangaPageResult {
-						entries: Vec::new(),
-						has_next_page: false,
-					});
-				}
-				format!(
-					"{BASE_URL}{LANG_PATH}/originals/{day}?sortOrder=MANA"
-				)
-			}
-			_ => bail!("Unknown listing: {}", listing.id),
-		};
+impl DynamicFilters for WebtoonSource {
+	fn get_dynamic_filters(&self) -> Result<Vec<Filter>> {
+		let client = client();
+		let lang = client.lang;
 
-		let (entries, has_next_page) = fetch_manga_list(&url)?;
+		let mut genre_options = Vec::new();
+		let mut genre_ids = Vec::new();
+		for (name, slug) in available_genres(&client, true) {
+			genre_options.push(name.into());
+			genre_ids.push(slug.into());
+		}
 
-		Ok(MangaPageResult {
-			entries,
-			has_next_page,
-		})
+		Ok(aidoku::alloc::vec![
+			MultiSelectFilter {
+				id: "genres".into(),
+				title: Some(lang.genre_title.into()),
+				is_genre: true,
+				can_exclude: true,
+				options: genre_options,
+				ids: Some(genre_ids),
+				..Default::default()
+			}
+			.into(),
+			SelectFilter {
+				id: "genre_match".into(),
+				title: Some(lang.genre_match_title.into()),
+				options: lang.genre_match_labels.iter().map(|label| (*label).into()).collect(),
+				ids: Some(aidoku::alloc::vec!["all".into(), GENRE_MATCH_ANY.into()]),
+				..Default::default()
+			}
+			.into(),
+			SelectFilter {
+				id: "sort".into(),
+				title: Some(lang.sort_title.into()),
+				options: lang.sort_labels.iter().map(|label| (*label).into()).collect(),
+				ids: Some(SORT_ORDERS.iter().map(|order| (*order).into()).collect()),
+				..Default::default()
+			}
+			.into(),
+		])
 	}
 }
 
@@ -286,32 +445,121 @@ impl ImageRequestProvider for WebtoonSource {
 		url: String,
 		_context: Option<PageContext>,
 	) -> Result<Request> {
+		// Applied here so library covers saved with older URLs follow the setting too
+		let url = apply_image_quality(strip_tile_fragment(&url), settings::get_image_quality());
 		let request = Request::get(&url)?
 			.header("Referer", "https://www.webtoons.com");
 		Ok(request)
 	}
 }
 
+impl PageImageProcessor for WebtoonSource {
+	fn process_page_image(
+		&self,
+		response: ImageResponse,
+		context: Option<PageContext>,
+	) -> Result<ImageRef> {
+		let image = response.image;
+		let tile = context.as_ref().and_then(page_tile);
+		let trim = settings::get_trim_margins();
+		if tile.is_none() && !trim {
+			return Ok(image);
+		}
+
+		// Tiles of one image share its trim, so it's only decoded once
+		let content = if trim {
+			let measure = || image_content_rows(&image.data());
+			let key = context
+				.as_ref()
+				.and_then(|context: &PageContext| context.get(IMAGE_CONTEXT_KEY));
+			match key {
+				Some(key) => self.content_rows.get(key, measure),
+				None => measure(),
+			}
+		} else {
+			None
+		};
+
+		let width = image.width();
+		let Some((top, bottom)) = page_crop(image.height(), tile, content) else {
+			return Ok(image);
+		};
+
+		let tile_height = (bottom - top).max(1.0);
+		let mut canvas = Canvas::new(width, tile_height);
+		canvas.copy_image(
+			&image,
+			Rect::new(0.0, top, width, tile_height),
+			Rect::new(0.0, 0.0, width, tile_height),
+		);
+		Ok(canvas.get_image())
+	}
+}
+
+impl WebLoginHandler for WebtoonSource {
+	fn handle_web_login(&self, _key: String, cookies: HashMap<String, String>) -> Result<bool> {
+		Ok(settings::set_session_cookies(&cookies))
+	}
+}
+
+impl NotificationHandler for WebtoonSource {
+	fn handle_notification(&self, notification: String) {
+		// Sent when the login setting changes; drop the cookies on logout.
+		if notification == "login" && !settings::is_logged_in() {
+			settings::clear_session();
+		}
+	}
+}
+
+impl MigrationHandler for WebtoonSource {
+	fn handle_manga_migration(&self, key: String) -> Result<String> {
+		Ok(key)
+	}
+
+	fn handle_chapter_migration(&self, manga_key: String, chapter_key: String) -> Result<String> {
+		let (_, _, title_no) = parse_manga_key(&manga_key);
+		Ok(migrate_chapter_key(title_no, &chapter_key).unwrap_or(chapter_key))
+	}
+}
+
 impl DeepLinkHandler for WebtoonSource {
 	fn handle_deep_link(&self, url: String) -> Result<Option<DeepLinkResult>> {
+		let url = normalize_deep_link(&url);
+
 		if let Some(title_no) = extract_title_no(&url) {
-			if url.contains("/viewer") {
-				Ok(Some(DeepLinkResult::Chapter {
-					manga_key: title_no,
-					key: url,
-				}))
+			// Titles open in the edition the link is for
+			let lang = url_language(&url).unwrap_or_else(settings::get_language);
+			let manga_key = make_manga_key(lang, TitleType::from_url(&url), &title_no);
+			if let Some(episode_no) = extract_episode_no(&url) {
+				// Same key as the chapters returned by get_manga_update
+				let key = make_chapter_key(&title_no, episode_no);
+				Ok(Some(DeepLinkResult::Chapter { manga_key, key }))
 			} else {
-				Ok(Some(DeepLinkResult::Manga { key: title_no }))
+				Ok(Some(DeepLinkResult::Manga { key: manga_key }))
 			}
+		} else if let Some((id, name)) = deep_link_listing(settings::get_language(), &url) {
+			Ok(Some(DeepLinkResult::Listing(Listing {
+				id,
+				name,
+				..Default::default()
+			})))
 		} else {
 			Ok(None)
 		}
 	}
 }
 
+#[cfg(not(test))]
 register_source!(
 	WebtoonSource,
+	Home,
 	ListingProvider,
+	DynamicListings,
+	DynamicFilters,
 	ImageRequestProvider,
-	DeepLinkHandler
+	PageImageProcessor,
+	DeepLinkHandler,
+	WebLoginHandler,
+	NotificationHandler,
+	MigrationHandler
 );
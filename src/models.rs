@@ -0,0 +1,88 @@
+use aidoku::alloc::{String, Vec};
+use serde::Deserialize;
+
+/// Envelope shared by all `m.webtoons.com/api/v1` responses.
+#[derive(Deserialize)]
+pub struct ApiResponse<T> {
+	#[serde(default)]
+	pub success: bool,
+	pub result: Option<T>,
+	pub error: Option<ApiError>,
+}
+
+#[derive(Deserialize)]
+pub struct ApiError {
+	pub message: Option<String>,
+}
+
+/// `result` of the `/{webtoon|canvas}/{titleNo}/episodes` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeListResult {
+	#[serde(default)]
+	pub episode_list: Vec<Episode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Episode {
+	pub episode_no: i32,
+	pub episode_title: Option<String>,
+	pub viewer_link: Option<String>,
+	pub thumbnail: Option<String>,
+	pub exposure_date_millis: Option<i64>,
+	/// `FREE` for readable episodes, otherwise e.g. `PAID`, `DAILY_PASS` or `FAST_PASS`.
+	pub product_type: Option<String>,
+	/// Set on episodes that need coins.
+	#[serde(default)]
+	pub charge: bool,
+	/// Set when the current session already owns the episode.
+	#[serde(default)]
+	pub purchased: bool,
+	/// When a Daily Pass or Fast Pass episode becomes free.
+	pub free_exposure_date_millis: Option<i64>,
+}
+
+impl Episode {
+	/// Whether the episode can't be read without paying or waiting.
+	pub fn is_locked(&self) -> bool {
+		if self.purchased {
+			return false;
+		}
+		self.charge
+			|| self
+				.product_type
+				.as_deref()
+				.is_some_and(|product: &str| product != "FREE")
+	}
+}
+
+/// `result` of the `/{webtoon|canvas}/{titleNo}` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleInfoResult {
+	pub title_info: TitleInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleInfo {
+	pub title: String,
+	pub writing_author_name: Option<String>,
+	pub picture_author_name: Option<String>,
+	/// Main genre code, e.g. `FANTASY` or `ROMANCE_M`.
+	pub represent_genre: Option<String>,
+	/// All genre and label codes, when the API lists more than the main one.
+	#[serde(default)]
+	pub genre_list: Vec<String>,
+	pub synopsis: Option<String>,
+	/// `SERIES`, `REST` or `TERMINATION`.
+	pub rest_termination_status: Option<String>,
+	pub thumbnail: Option<String>,
+	pub thumbnail_vertical: Option<String>,
+	#[serde(default)]
+	pub age_grade_notice: bool,
+	/// Update days, e.g. `["SATURDAY"]`.
+	#[serde(default)]
+	pub weekday: Vec<String>,
+}
@@ -0,0 +1,145 @@
+use aidoku::{
+	alloc::String,
+	imports::{
+		html::{Document, Html},
+		net::{Request, Response},
+		std::sleep,
+	},
+	prelude::*,
+	Result,
+};
+
+use crate::{
+	helper::cookie_value,
+	lang::Language,
+	node::Node,
+	settings,
+	transport::{HttpResponse, Transport},
+	BASE_URL,
+};
+
+const USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+/// Cookies that pass the age gate and GDPR/CCPA consent interstitials.
+const CONSENT_COOKIES: &str =
+	"ageGatePass=true; needGDPR=false; needCCPA=false; needCOPPA=false; pagGDPR=true";
+
+/// How many times a transient failure (429 or 5xx) is retried.
+const MAX_RETRIES: i32 = 3;
+
+/// The app's network stack.
+///
+/// Sends the shared headers with the consent, locale and session cookies,
+/// retries transient failures with backoff and keeps the session fresh.
+pub struct AidokuTransport {
+	/// The edition sent as the `locale` cookie.
+	pub lang: &'static Language,
+}
+
+impl Transport for AidokuTransport {
+	type Document = Document;
+
+	fn get(&self, url: &str) -> Result<HttpResponse> {
+		let mut attempt = 0;
+		loop {
+			let response = request(url, self.lang)?.send()?;
+			let status = response.status_code();
+
+			if (status == 429 || (500..600).contains(&status)) && attempt < MAX_RETRIES {
+				attempt += 1;
+				// 1s, 2s, 4s
+				sleep(1 << (attempt - 1));
+				continue;
+			}
+
+			check_session(&response)?;
+
+			let body = if (200..400).contains(&status) {
+				response.get_string()?
+			} else {
+				String::new()
+			};
+			return Ok(HttpResponse { status, body });
+		}
+	}
+
+	fn parse_html(&self, url: &str, body: &str) -> Result<Document> {
+		Html::parse_with_url(body, url).map_err(|_| error!("Invalid HTML from {url}"))
+	}
+}
+
+/// Build a GET request with the shared headers, the consent and locale
+/// cookies, and the signed-in session, if any.
+fn request(url: &str, lang: &Language) -> Result<Request> {
+	let mut cookies = format!("{CONSENT_COOKIES}; locale={}", lang.id);
+	if let Some(session) = settings::get_session_cookies() {
+		cookies.push_str("; ");
+		cookies.push_str(&session);
+	}
+	Ok(Request::get(url)?
+		.header("Referer", BASE_URL)
+		.header("User-Agent", USER_AGENT)
+		.header("Cookie", &cookies))
+}
+
+/// Send a GET request through `transport`.
+///
+/// Non-success status codes are mapped to errors the app can show.
+pub fn send<T: Transport>(transport: &T, url: &str) -> Result<HttpResponse> {
+	let response = transport.get(url)?;
+	let status = response.status;
+	match status {
+		200..=399 => Ok(response),
+		401 => bail!("Webtoons requires login for this content (401)"),
+		403 => bail!("Webtoons denied access to this content (403)"),
+		404 => bail!("Not found on Webtoons, it may have been removed (404)"),
+		451 => bail!("This content is not available in your region (451)"),
+		429 => bail!("Too many requests to Webtoons, please try again later (429)"),
+		500..=599 => bail!("Webtoons is temporarily unavailable ({status})"),
+		_ => bail!("Unexpected response from Webtoons ({status})"),
+	}
+}
+
+/// Fetch and parse an HTML page, see [`send`].
+pub fn get_html<T: Transport>(transport: &T, url: &str) -> Result<T::Document> {
+	let response = send(transport, url)?;
+	let html = transport.parse_html(url, &response.body)?;
+	if html.select_first("#_regionBlock, .region_block, .error_area.region").is_some() {
+		bail!("This content is not available in your region");
+	}
+	Ok(html)
+}
+
+/// Fetch a response body as text, see [`send`].
+pub fn get_string<T: Transport>(transport: &T, url: &str) -> Result<String> {
+	Ok(send(transport, url)?.body)
+}
+
+/// Keep the signed-in session fresh.
+///
+/// Rotated session cookies are stored, and a request rejected while signed in
+/// clears the session so the user is asked to log in again.
+fn check_session(response: &Response) -> Result<()> {
+	if !settings::is_logged_in() {
+		return Ok(());
+	}
+
+	if response.status_code() == 401
+		|| response
+			.get_header("Location")
+			.is_some_and(|location: String| location.contains("/member/login"))
+	{
+		settings::clear_session();
+		bail!("Webtoons login has expired, please log in again");
+	}
+
+	if let Some(set_cookie) = response.get_header("Set-Cookie") {
+		for name in settings::SESSION_COOKIE_NAMES {
+			if let Some(value) = cookie_value(&set_cookie, name) {
+				settings::update_session_cookie(name, value);
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,70 @@
+use aidoku::{
+	alloc::{String, Vec},
+	imports::html::{Document, Element},
+};
+
+/// The parts of an HTML document the parsers in [`crate::helper`] rely on.
+///
+/// Implemented for the app's [`Document`] and [`Element`], and for a host
+/// HTML parser in tests, so the parsing logic can run under `cargo test`.
+pub trait Node {
+	type Element: Node;
+
+	/// All descendants matching a CSS selector, in document order.
+	fn select(&self, selector: &str) -> Vec<Self::Element>;
+
+	/// The first descendant matching a CSS selector.
+	fn select_first(&self, selector: &str) -> Option<Self::Element> {
+		self.select(selector).into_iter().next()
+	}
+
+	/// The value of an attribute.
+	fn attr(&self, name: &str) -> Option<String>;
+
+	/// The combined, whitespace-normalized text of the node.
+	fn text(&self) -> Option<String>;
+}
+
+impl Node for Document {
+	type Element = Element;
+
+	fn select(&self, selector: &str) -> Vec<Element> {
+		Document::select(self, selector)
+			.map(|list| list.into_iter().collect())
+			.unwrap_or_default()
+	}
+
+	fn select_first(&self, selector: &str) -> Option<Element> {
+		Document::select_first(self, selector)
+	}
+
+	fn attr(&self, _name: &str) -> Option<String> {
+		None
+	}
+
+	fn text(&self) -> Option<String> {
+		Document::text(self)
+	}
+}
+
+impl Node for Element {
+	type Element = Element;
+
+	fn select(&self, selector: &str) -> Vec<Element> {
+		Element::select(self, selector)
+			.map(|list| list.into_iter().collect())
+			.unwrap_or_default()
+	}
+
+	fn select_first(&self, selector: &str) -> Option<Element> {
+		Element::select_first(self, selector)
+	}
+
+	fn attr(&self, name: &str) -> Option<String> {
+		Element::attr(self, name)
+	}
+
+	fn text(&self) -> Option<String> {
+		Element::text(self)
+	}
+}
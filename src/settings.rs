@@ -0,0 +1,126 @@
+use aidoku::{
+	alloc::{String, Vec},
+	imports::defaults::{defaults_get, defaults_set, DefaultValue},
+	HashMap,
+};
+
+use crate::helper::{GenreEntry, ImageQuality};
+use crate::lang::{language_by_id, Language, SORT_ORDERS};
+
+const LANGUAGE_KEY: &str = "language";
+const LISTING_SORT_KEY: &str = "listing_sort";
+const IMAGE_QUALITY_KEY: &str = "image_quality";
+const TILE_HEIGHT_KEY: &str = "tile_height";
+const TRIM_MARGINS_KEY: &str = "trim_margins";
+const LOGIN_KEY: &str = "login";
+const GENRES_KEY_PREFIX: &str = "genres.";
+const SESSION_COOKIES_KEY: &str = "session_cookies";
+
+/// Cookies set by Webtoons once an account is signed in.
+pub const SESSION_COOKIE_NAMES: [&str; 2] = ["NEO_SES", "NEO_CHK"];
+
+/// The language edition selected in settings.
+pub fn get_language() -> &'static Language {
+	let id = defaults_get::<String>(LANGUAGE_KEY).unwrap_or_default();
+	language_by_id(&id)
+}
+
+/// The `sortOrder` used by the weekday and completed listings.
+pub fn get_listing_sort() -> &'static str {
+	let value = defaults_get::<String>(LISTING_SORT_KEY).unwrap_or_default();
+	SORT_ORDERS
+		.iter()
+		.find(|order| **order == value)
+		.copied()
+		.unwrap_or(SORT_ORDERS[0])
+}
+
+/// The image quality covers, thumbnails and pages are requested in.
+pub fn get_image_quality() -> ImageQuality {
+	let value = defaults_get::<String>(IMAGE_QUALITY_KEY).unwrap_or_default();
+	ImageQuality::from_setting(&value)
+}
+
+/// The tallest page images are shown at before being split into tiles, if
+/// splitting is enabled.
+pub fn get_tile_height() -> Option<u32> {
+	let value = defaults_get::<String>(TILE_HEIGHT_KEY).unwrap_or_default();
+	value.parse().ok().filter(|height: &u32| *height > 0)
+}
+
+/// Whether uniform white or black margins are trimmed from page images.
+pub fn get_trim_margins() -> bool {
+	defaults_get::<bool>(TRIM_MARGINS_KEY).unwrap_or(false)
+}
+
+/// Genres last fetched from the site for a language edition.
+pub fn get_cached_genres(lang: &Language) -> Option<Vec<GenreEntry>> {
+	let key = aidoku::alloc::format!("{GENRES_KEY_PREFIX}{}", lang.id);
+	let genres: Vec<GenreEntry> = defaults_get::<Vec<String>>(&key)?
+		.into_iter()
+		.filter_map(|entry: String| {
+			let (slug, name) = entry.split_once('=')?;
+			Some((String::from(name), String::from(slug)))
+		})
+		.collect();
+	(!genres.is_empty()).then_some(genres)
+}
+
+/// Cache the genres fetched from the site, stored as `slug=name` entries.
+pub fn set_cached_genres(lang: &Language, genres: &[GenreEntry]) {
+	let key = aidoku::alloc::format!("{GENRES_KEY_PREFIX}{}", lang.id);
+	let entries: Vec<String> = genres
+		.iter()
+		.map(|(name, slug)| aidoku::alloc::format!("{slug}={name}"))
+		.collect();
+	defaults_set(&key, DefaultValue::StringArray(entries));
+}
+
+/// Whether the login setting is currently signed in.
+pub fn is_logged_in() -> bool {
+	defaults_get::<bool>(LOGIN_KEY).unwrap_or(false)
+		|| defaults_get::<String>(LOGIN_KEY).is_some_and(|value: String| !value.is_empty())
+}
+
+/// The stored session as a `Cookie` header value, if signed in.
+pub fn get_session_cookies() -> Option<String> {
+	if !is_logged_in() {
+		return None;
+	}
+	defaults_get::<String>(SESSION_COOKIES_KEY).filter(|cookies: &String| !cookies.is_empty())
+}
+
+/// Store the cookies captured by the web login.
+///
+/// Only the session cookies are kept; returns false if none were present.
+pub fn set_session_cookies(cookies: &HashMap<String, String>) -> bool {
+	let pairs: Vec<String> = SESSION_COOKIE_NAMES
+		.iter()
+		.filter_map(|name| cookies.get(*name).map(|value| aidoku::alloc::format!("{name}={value}")))
+		.collect();
+	if pairs.is_empty() {
+		return false;
+	}
+	defaults_set(SESSION_COOKIES_KEY, DefaultValue::String(pairs.join("; ")));
+	true
+}
+
+/// Replace a single cookie of the stored session, e.g. after the site rotated it.
+pub fn update_session_cookie(name: &str, value: &str) {
+	let Some(cookies) = get_session_cookies() else {
+		return;
+	};
+	let mut pairs: Vec<String> = cookies
+		.split("; ")
+		.filter(|pair: &&str| pair.split('=').next() != Some(name))
+		.map(String::from)
+		.collect();
+	pairs.push(aidoku::alloc::format!("{name}={value}"));
+	defaults_set(SESSION_COOKIES_KEY, DefaultValue::String(pairs.join("; ")));
+}
+
+/// Forget the stored session and sign the login setting out.
+pub fn clear_session() {
+	defaults_set(SESSION_COOKIES_KEY, DefaultValue::Null);
+	defaults_set(LOGIN_KEY, DefaultValue::Null);
+}
@@ -0,0 +1,866 @@
+//! Host tests of the parsing helpers and source flows against saved
+//! Webtoons responses.
+//!
+//! Fixtures live in `tests/fixtures` and are parsed with `scraper` through
+//! the [`Node`] trait, the same interface the app's HTML parser implements.
+//! Whole flows replay the responses recorded in `flow.json`.
+
+use aidoku::{
+	Chapter, ContentRating, FilterValue, Manga, MangaStatus, Page, PageContent, Viewer,
+};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+
+use crate::client::{Client, ListingCache};
+use crate::helper::*;
+use crate::lang::{Language, EN, ZH_HANT};
+use crate::node::Node;
+use crate::transport::{Exchange, HttpResponse, ReplayTransport};
+
+const LANG: &Language = &ZH_HANT;
+
+impl<'a> Node for ElementRef<'a> {
+	type Element = ElementRef<'a>;
+
+	fn select(&self, selector: &str) -> Vec<ElementRef<'a>> {
+		let selector = Selector::parse(selector).expect("invalid selector");
+		ElementRef::select(self, &selector).collect()
+	}
+
+	fn attr(&self, name: &str) -> Option<String> {
+		self.value().attr(name).map(String::from)
+	}
+
+	fn text(&self) -> Option<String> {
+		let words: Vec<&str> = ElementRef::text(self)
+			.flat_map(|text: &str| text.split_whitespace())
+			.collect();
+		Some(words.join(" "))
+	}
+}
+
+macro_rules! fixture {
+	($name:literal) => {
+		include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/", $name))
+	};
+}
+
+fn strings(values: &[&str]) -> Option<Vec<String>> {
+	Some(values.iter().map(|value: &&str| String::from(*value)).collect())
+}
+
+fn page_url(page: &Page) -> &str {
+	match &page.content {
+		PageContent::Url(url, _) => url,
+		_ => panic!("page is not a URL"),
+	}
+}
+
+/// An entry of `flow.json`: a recorded response, its body saved as a fixture.
+#[derive(Deserialize)]
+struct Recording {
+	url: String,
+	status: i32,
+	file: Option<String>,
+}
+
+fn parse_document(_url: &str, body: &str) -> aidoku::Result<ElementRef<'static>> {
+	// Leaked so parsed elements can outlive the replayed response
+	let html: &'static Html = Box::leak(Box::new(Html::parse_document(body)));
+	Ok(html.root_element())
+}
+
+fn replay_client() -> Client<ReplayTransport<ElementRef<'static>>> {
+	let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+	let recordings: Vec<Recording> =
+		serde_json::from_str(fixture!("flow.json")).expect("invalid flow.json");
+	let exchanges = recordings
+		.into_iter()
+		.map(|recording: Recording| {
+			let body = recording
+				.file
+				.map(|file: String| {
+					std::fs::read_to_string(format!("{dir}/{file}")).expect("missing fixture")
+				})
+				.unwrap_or_default();
+			Exchange {
+				url: recording.url,
+				response: HttpResponse {
+					status: recording.status,
+					body,
+				},
+			}
+		})
+		.collect();
+	Client::new(ReplayTransport::new(exchanges, parse_document), LANG)
+}
+
+#[test]
+fn genre_listing() {
+	let html = Html::parse_document(fixture!("genre_list.html"));
+	let (entries, has_next_page) = parse_manga_list(LANG, &html.root_element());
+
+	assert!(has_next_page);
+	assert_eq!(entries.len(), 2, "items without a title are skipped");
+
+	let reader = &entries[0];
+	assert_eq!(reader.key, "2089");
+	assert_eq!(reader.title, "全知讀者視角");
+	assert_eq!(
+		reader.cover.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20200602_1/thumb_omniscient.jpg")
+	);
+	assert_eq!(reader.authors, strings(&["sing N song"]));
+	assert_eq!(reader.artists, strings(&["UMI"]));
+	assert_eq!(reader.tags, strings(&["奇幻冒險"]));
+	assert!(matches!(reader.content_rating, ContentRating::Safe));
+
+	let lady = &entries[1];
+	assert_eq!(lady.key, "1532");
+	assert_eq!(lady.authors, strings(&["Yuri (原著)"]));
+	assert_eq!(lady.artists, strings(&["Mina"]));
+	assert_eq!(lady.tags, strings(&["大人系"]));
+	assert!(matches!(lady.content_rating, ContentRating::NSFW));
+}
+
+#[test]
+fn search_results() {
+	let html = Html::parse_document(fixture!("search.html"));
+	let (entries, has_next_page) = parse_manga_list(LANG, &html.root_element());
+
+	assert!(!has_next_page);
+	assert_eq!(entries.len(), 2);
+
+	let original = &entries[0];
+	assert_eq!(original.key, "1285");
+	assert_eq!(original.title, "Sweet Home");
+	assert_eq!(original.authors, strings(&["金坎比"]));
+	assert_eq!(original.artists, strings(&["黃英燦"]));
+	assert_eq!(original.tags, strings(&["驚悚"]));
+	assert!(matches!(original.content_rating, ContentRating::Suggestive));
+
+	let canvas = &entries[1];
+	assert_eq!(canvas.key, "canvas:700123");
+	assert_eq!(canvas.title, "我的貓咪日記");
+	assert_eq!(
+		canvas.cover.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20230301_7/thumb_cat.png")
+	);
+	assert_eq!(canvas.authors, strings(&["小花"]));
+	assert_eq!(canvas.artists, strings(&["小花"]));
+	assert_eq!(canvas.tags, strings(&["生活/日常"]));
+	assert!(matches!(canvas.content_rating, ContentRating::Safe));
+}
+
+#[test]
+fn series_details() {
+	let html = Html::parse_document(fixture!("detail.html"));
+	let mut manga = Manga {
+		key: String::from("2089"),
+		..Default::default()
+	};
+	parse_manga_details(LANG, &html.root_element(), &mut manga);
+
+	assert_eq!(manga.title, "全知讀者視角");
+	assert_eq!(manga.authors, strings(&["sing N song (原著)", "Sleepy-C"]));
+	assert_eq!(manga.artists, strings(&["UMI"]));
+	assert_eq!(
+		manga.description.as_deref(),
+		Some("唯一讀完小說結局的讀者，踏入了小說成為現實的世界。\n\n每週六更新")
+	);
+	// Genres of recommended titles stay out of the tags and rating
+	assert_eq!(manga.tags, strings(&["奇幻冒險"]));
+	assert_eq!(
+		manga.cover.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20200602_1/og_omniscient.jpg")
+	);
+	assert!(matches!(manga.status, MangaStatus::Ongoing));
+	assert!(matches!(manga.content_rating, ContentRating::Safe));
+}
+
+#[test]
+fn title_info_api() {
+	let info = parse_title_info_json(fixture!("title_info.json")).expect("title info");
+	let mut manga = Manga::default();
+	apply_title_info(LANG, info, &mut manga);
+
+	assert_eq!(manga.title, "騎士與淑女");
+	assert_eq!(manga.authors, strings(&["Yuri (原著)"]));
+	assert_eq!(manga.artists, strings(&["Mina"]));
+	assert_eq!(manga.tags, strings(&["大人系", "歐式宮廷"]));
+	assert_eq!(
+		manga.cover.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20190118_2/vertical_lady.jpg")
+	);
+	assert_eq!(
+		manga.description.as_deref(),
+		Some("沒落貴族千金與騎士的故事。\n\n更新日：週六")
+	);
+	assert!(matches!(manga.status, MangaStatus::Ongoing));
+	assert!(matches!(manga.content_rating, ContentRating::NSFW));
+}
+
+#[test]
+fn title_info_keeps_original_credits() {
+	let body = r#"{"result":{"titleInfo":{"title":"全知讀者視角","writingAuthorName":"Sleepy-C","pictureAuthorName":"UMI"}},"success":true}"#;
+	let info = parse_title_info_json(body).expect("title info");
+	let mut manga = Manga {
+		authors: strings(&["sing N song (原著)", "Sleepy-C"]),
+		..Default::default()
+	};
+	apply_title_info(LANG, info, &mut manga);
+
+	assert_eq!(manga.authors, strings(&["sing N song (原著)", "Sleepy-C"]));
+	assert_eq!(manga.artists, strings(&["UMI"]));
+}
+
+#[test]
+fn unlabelled_credits() {
+	let credits = |text: &str| {
+		let mut manga = Manga::default();
+		parse_credits(LANG, text).apply(&mut manga);
+		(manga.authors, manga.artists)
+	};
+	assert_eq!(credits("小花"), (strings(&["小花"]), strings(&["小花"])));
+	assert_eq!(credits("金坎比 / 黃英燦"), (strings(&["金坎比"]), strings(&["黃英燦"])));
+	// Several names in one group aren't split into roles
+	assert_eq!(credits("A, B, C"), (strings(&["A", "B", "C"]), None));
+	assert_eq!(credits("甲、乙"), (strings(&["甲", "乙"]), None));
+}
+
+#[test]
+fn description_refresh() {
+	// Refreshing details rebuilds the schedule line instead of repeating it
+	let body = r#"{"result":{"titleInfo":{"title":"騎士與淑女","restTerminationStatus":"SERIES","weekday":["SATURDAY"]}},"success":true}"#;
+	let mut manga = Manga::default();
+	for _ in 0..2 {
+		apply_title_info(LANG, parse_title_info_json(body).expect("title info"), &mut manga);
+	}
+	assert_eq!(manga.description.as_deref(), Some("更新日：週六"));
+
+	let html = Html::parse_document(fixture!("detail.html"));
+	for _ in 0..2 {
+		parse_manga_details(LANG, &html.root_element(), &mut manga);
+	}
+	assert_eq!(
+		manga.description.as_deref(),
+		Some("唯一讀完小說結局的讀者，踏入了小說成為現實的世界。\n\n每週六更新")
+	);
+}
+
+#[test]
+fn episodes_api() {
+	let chapters = parse_episodes_json(LANG, "2089", fixture!("episodes.json")).expect("episodes");
+	assert_eq!(chapters.len(), 3);
+
+	let keys: Vec<&str> = chapters.iter().map(|c| c.key.as_str()).collect();
+	assert_eq!(keys, ["2089/3", "2089/2", "2089/1"], "newest first");
+
+	let locked = &chapters[0];
+	assert!(locked.locked);
+	assert_eq!(locked.title.as_deref(), Some("第3話 終章 (免費開放 2026-01-01)"));
+	assert_eq!(locked.chapter_number, Some(3.0));
+	assert_eq!(locked.thumbnail, None);
+
+	let purchased = &chapters[1];
+	assert!(!purchased.locked);
+	// `\uXXXX` escapes, including a surrogate pair, and `\/` slashes
+	assert_eq!(purchased.title.as_deref(), Some("第2話 🔒"));
+	assert_eq!(
+		purchased.url.as_deref(),
+		Some("https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/ep-2/viewer?title_no=2089&episode_no=2")
+	);
+	assert_eq!(
+		purchased.thumbnail.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20200609_1/ep2.jpg")
+	);
+
+	let first = &chapters[2];
+	assert!(!first.locked);
+	assert_eq!(first.title.as_deref(), Some("第1話 \"序章\""));
+	assert_eq!(first.date_uploaded, Some(1_591_056_000));
+	assert_eq!(
+		first.url.as_deref(),
+		Some("https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/ep-1/viewer?title_no=2089&episode_no=1")
+	);
+	assert_eq!(
+		first.thumbnail.as_deref(),
+		Some("https://webtoon-phinf.pstatic.net/20200602_1/ep1.jpg")
+	);
+}
+
+#[test]
+fn episodes_api_error() {
+	assert!(parse_episodes_json(LANG, "2089", fixture!("episodes_error.json")).is_err());
+	assert!(parse_episodes_json(LANG, "2089", "<html>").is_err());
+}
+
+#[test]
+fn viewer_pages() {
+	let html = Html::parse_document(fixture!("viewer.html"));
+	let pages = parse_viewer_pages(&html.root_element(), None).expect("pages");
+
+	let urls: Vec<&str> = pages.iter().map(page_url).collect();
+	assert_eq!(
+		urls,
+		[
+			"https://webtoon-phinf.pstatic.net/20240101_1/001.jpg?type=q90",
+			"https://webtoon-phinf.pstatic.net/20240101_1/002.jpg?type=q90",
+			"https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90",
+		]
+	);
+}
+
+#[test]
+fn viewer_tiles() {
+	let html = Html::parse_document(fixture!("viewer.html"));
+	let pages = parse_viewer_pages(&html.root_element(), Some(4000)).expect("pages");
+
+	let tiles: Vec<Option<(u32, u32)>> = pages
+		.iter()
+		.map(|page: &Page| match &page.content {
+			PageContent::Url(_, Some(context)) => page_tile(context),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(tiles, [None, None, Some((0, 3)), Some((1, 3)), Some((2, 3))]);
+
+	// Tiles have URLs of their own, requested as the image they're cut from
+	let urls: Vec<&str> = pages[2..].iter().map(page_url).collect();
+	assert_eq!(
+		urls,
+		[
+			"https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90#tile=0/3",
+			"https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90#tile=1/3",
+			"https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90#tile=2/3",
+		]
+	);
+	assert!(urls.iter().all(|url: &&str| {
+		strip_tile_fragment(url) == "https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90"
+	}));
+	assert_eq!(strip_tile_fragment(page_url(&pages[0])), page_url(&pages[0]));
+
+	// Tiles name the viewer's image, whatever quality it's requested in
+	let image = "https://webtoon-phinf.pstatic.net/20240101_1/003.jpg?type=q90";
+	let image_of = |page: &Page| match &page.content {
+		PageContent::Url(_, Some(context)) => context.get(IMAGE_CONTEXT_KEY).cloned(),
+		_ => None,
+	};
+	assert_eq!(image_of(&pages[0]), None);
+	assert!(pages[2..].iter().all(|page: &Page| image_of(page).as_deref() == Some(image)));
+}
+
+#[test]
+fn viewer_age_gate() {
+	let html = Html::parse_document(fixture!("age_gate.html"));
+	assert!(is_age_gate(&html.root_element()));
+	assert!(!is_consent_page(&html.root_element()));
+	assert!(parse_viewer_pages(&html.root_element(), None).is_err());
+
+	let html = Html::parse_document(fixture!("consent.html"));
+	assert!(is_consent_page(&html.root_element()));
+	assert!(!is_age_gate(&html.root_element()));
+	assert!(parse_viewer_pages(&html.root_element(), None).is_err());
+}
+
+#[test]
+fn title_and_episode_numbers() {
+	let url = "https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/ep-3/viewer?title_no=2089&episode_no=3";
+	assert_eq!(extract_title_no(url).as_deref(), Some("2089"));
+	assert_eq!(extract_episode_no(url), Some(3));
+	assert_eq!(extract_title_no("webtoon://viewer?titleNo=95&episodeNo=7").as_deref(), Some("95"));
+	assert_eq!(extract_title_no("https://www.webtoons.com/zh-hant/list?title_no="), None);
+	assert_eq!(extract_title_no("https://www.webtoons.com/zh-hant/"), None);
+}
+
+#[test]
+fn chapter_key_migration() {
+	let old_key = "https://www.webtoons.com/zh-hant/fantasy/a/ep-3/viewer?title_no=2089&episode_no=3";
+	assert_eq!(migrate_chapter_key("2089", old_key).as_deref(), Some("2089/3"));
+	assert_eq!(migrate_chapter_key("2089", "12").as_deref(), Some("2089/12"));
+	assert_eq!(migrate_chapter_key("2089", "2089/12").as_deref(), Some("2089/12"));
+	assert_eq!(migrate_chapter_key("2089", "not-a-chapter"), None);
+}
+
+#[test]
+fn edition_keys() {
+	// Traditional Chinese keeps bare keys from before editions were recorded
+	assert_eq!(make_manga_key(LANG, TitleType::Originals, "2089"), "2089");
+	assert_eq!(make_manga_key(LANG, TitleType::Canvas, "700123"), "canvas:700123");
+	assert_eq!(make_manga_key(&EN, TitleType::Originals, "95"), "en:95");
+	assert_eq!(make_manga_key(&EN, TitleType::Canvas, "700123"), "en:canvas:700123");
+
+	let edition = |key: &str| {
+		let (lang, title_type, title_no) = parse_manga_key(key);
+		(lang.id, title_type == TitleType::Canvas, String::from(title_no))
+	};
+	assert_eq!(edition("2089"), ("zh-hant", false, String::from("2089")));
+	assert_eq!(edition("canvas:700123"), ("zh-hant", true, String::from("700123")));
+	assert_eq!(edition("en:95"), ("en", false, String::from("95")));
+	assert_eq!(edition("en:canvas:700123"), ("en", true, String::from("700123")));
+
+	let html = Html::parse_document(fixture!("search.html"));
+	let (entries, _) = parse_manga_list(&EN, &html.root_element());
+	let keys: Vec<&str> = entries.iter().map(|manga: &Manga| manga.key.as_str()).collect();
+	assert_eq!(keys, ["en:1285", "en:canvas:700123"]);
+}
+
+#[test]
+fn deep_link_normalization() {
+	let share = "https://app.webtoons.com/share?url=https%3A%2F%2Fm.webtoons.com%2Fzh-hant%2Ffantasy%2Fa%2Flist%3Ftitle_no%3D2089";
+	assert_eq!(
+		normalize_deep_link(share),
+		"https://www.webtoons.com/zh-hant/fantasy/a/list?title_no=2089"
+	);
+	let encoded = "https://m.webtoons.com/zh-hant/fantasy/a/list%3ftitle_no%3d2089";
+	assert_eq!(
+		normalize_deep_link(encoded),
+		"https://www.webtoons.com/zh-hant/fantasy/a/list?title_no=2089"
+	);
+	assert_eq!(
+		url_path_segments("https://www.webtoons.com/zh-hant/genres/romance?page=2"),
+		["zh-hant", "genres", "romance"]
+	);
+
+	// Listings opened from links carry the same names as the listings menu
+	let listing = |url: &str| crate::deep_link_listing(LANG, url);
+	assert_eq!(
+		listing("https://www.webtoons.com/zh-hant/challenge/list?genreTab=ALL"),
+		Some((String::from("canvas"), String::from("挑戰聯盟")))
+	);
+	assert_eq!(
+		listing("https://www.webtoons.com/zh-hant/ranking/trending"),
+		Some((String::from("ranking_trending"), String::from("熱門趨勢排行")))
+	);
+	assert_eq!(
+		listing("https://www.webtoons.com/zh-hant/originals?weekday=SATURDAY"),
+		Some((String::from("saturday"), String::from("週六")))
+	);
+
+	// Links name their edition, and other editions' listings aren't opened
+	let edition = |url: &str| url_language(url).map(|lang: &Language| lang.id);
+	assert_eq!(edition("https://www.webtoons.com/en/fantasy/a/list?title_no=95"), Some("en"));
+	assert_eq!(edition(&normalize_deep_link(share)), Some("zh-hant"));
+	assert_eq!(edition("webtoon://viewer?titleNo=95&episodeNo=7"), None);
+	assert_eq!(listing("https://www.webtoons.com/en/ranking/trending"), None);
+}
+
+#[test]
+fn listing_merge_and_paging() {
+	let manga = |key: &str| Manga {
+		key: String::from(key),
+		..Default::default()
+	};
+	let merged = merge_listings(vec![
+		vec![manga("1"), manga("2"), manga("3")],
+		vec![manga("2"), manga("4")],
+	]);
+	let keys: Vec<&str> = merged.iter().map(|m| m.key.as_str()).collect();
+	assert_eq!(keys, ["1", "2", "3", "4"]);
+
+	let entries: Vec<Manga> = (0..MERGED_PAGE_SIZE + 5).map(|i| manga(&i.to_string())).collect();
+	let first = paginate(&entries, 1);
+	assert!(first.has_next_page);
+	assert_eq!(first.entries.len(), MERGED_PAGE_SIZE);
+	let second = paginate(&entries, 2);
+	assert!(!second.has_next_page);
+	assert_eq!(second.entries.len(), 5);
+}
+
+#[test]
+fn dates() {
+	assert_eq!(format_date(0), "1970-01-01");
+	assert_eq!(format_date(1_767_225_600), "2026-01-01");
+	assert_eq!(format_date(951_782_400), "2000-02-29");
+}
+
+#[test]
+fn search_to_pages_flow() {
+	let client = replay_client();
+	let genres = static_genres(LANG);
+
+	let results = client
+		.search(
+			&ListingCache::default(),
+			&genres,
+			Some(String::from("全知讀者視角")),
+			1,
+			Vec::new(),
+		)
+		.expect("search");
+	assert!(results.has_next_page);
+	let manga = results.entries.into_iter().next().expect("no search results");
+	assert_eq!(manga.key, "2089");
+
+	// A sort the results can't follow is reported rather than ignored
+	let sorted = client.search(
+		&ListingCache::default(),
+		&genres,
+		Some(String::from("全知讀者視角")),
+		1,
+		vec![FilterValue::Select {
+			id: String::from("sort"),
+			value: String::from("LIKEIT"),
+		}],
+	);
+	assert!(sorted.is_err());
+
+	// The title info API is down, so details come from the series page
+	let manga = client.manga_update(manga, true, true).expect("manga update");
+	assert_eq!(manga.title, "全知讀者視角");
+	assert_eq!(manga.artists, strings(&["UMI"]));
+	assert!(matches!(manga.viewer, Viewer::Webtoon));
+	let chapters = manga.chapters.as_ref().expect("no chapters");
+	assert_eq!(chapters.len(), 3);
+
+	// The stored viewer link matches the chapter key, so it's used as is
+	let first = chapters.last().expect("no first episode");
+	let pages = client.page_list(&manga, first, None).expect("page list");
+	assert_eq!(pages.len(), 3);
+
+	assert_eq!(
+		client.transport.requests(),
+		[
+			"https://www.webtoons.com/zh-hant/search/originals?keyword=%E5%85%A8%E7%9F%A5%E8%AE%80%E8%80%85%E8%A6%96%E8%A7%92&page=1",
+			"https://www.webtoons.com/zh-hant/search/canvas?keyword=%E5%85%A8%E7%9F%A5%E8%AE%80%E8%80%85%E8%A6%96%E8%A7%92&page=1",
+			"https://m.webtoons.com/api/v1/webtoon/2089",
+			"https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/list?title_no=2089",
+			"https://m.webtoons.com/api/v1/webtoon/2089/episodes?pageSize=99999",
+			"https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/ep-1/viewer?title_no=2089&episode_no=1",
+		]
+	);
+}
+
+#[test]
+fn all_originals_flow() {
+	let client = replay_client();
+	let cache = ListingCache::default();
+	let genres = static_genres(LANG);
+
+	let first = client.search(&cache, &genres, None, 1, Vec::new()).expect("first page");
+	let keys: Vec<&str> = first.entries.iter().map(|m| m.key.as_str()).collect();
+	assert_eq!(keys, ["2089", "1532"]);
+	assert!(!first.has_next_page);
+	assert_eq!(client.transport.requests().len(), 8, "one request per weekday page");
+
+	// Later pages come from the merged listing instead of refetching it
+	let second = client.search(&cache, &genres, None, 2, Vec::new()).expect("second page");
+	assert!(second.entries.is_empty());
+	assert_eq!(client.transport.requests().len(), 8);
+
+	// Going back to the first page refreshes it
+	client.search(&cache, &genres, None, 1, Vec::new()).expect("refresh");
+	assert_eq!(client.transport.requests().len(), 16);
+
+	// Every weekday page follows the sort filter, cached apart from the others
+	let by_likes = || {
+		vec![FilterValue::Select {
+			id: String::from("sort"),
+			value: String::from("愛心排序"),
+		}]
+	};
+	let sorted = client.search(&cache, &genres, None, 2, by_likes()).expect("sorted");
+	assert!(sorted.entries.is_empty());
+	let requests = client.transport.requests();
+	assert_eq!(requests.len(), 24);
+	assert!(requests[16..].iter().all(|url: &String| url.ends_with("?sortOrder=LIKEIT")));
+}
+
+#[test]
+fn combined_genres_flow() {
+	let client = replay_client();
+	let cache = ListingCache::default();
+	// The site no longer offers the thriller genre, still selected below
+	let genres: Vec<GenreEntry> = static_genres(LANG)
+		.into_iter()
+		.filter(|(_, slug): &GenreEntry| slug != "thriller")
+		.collect();
+	let filters = |included: &[&str]| {
+		vec![
+			FilterValue::MultiSelect {
+				id: String::from("genres"),
+				included: included.iter().map(|genre: &&str| String::from(*genre)).collect(),
+				excluded: Vec::new(),
+			},
+			FilterValue::Select {
+				id: String::from("genre_match"),
+				value: String::from("any"),
+			},
+		]
+	};
+	let selected = ["奇幻冒險", "大人系", "驚悚"];
+
+	// Genres the site dropped are skipped, and every page of the others is combined
+	let first = client.search(&cache, &genres, None, 1, filters(&selected)).expect("first page");
+	let keys: Vec<&str> = first.entries.iter().map(|m| m.key.as_str()).collect();
+	assert_eq!(keys, ["2089", "1285", "1532", "canvas:700123"]);
+	assert_eq!(
+		client.transport.requests(),
+		[
+			"https://www.webtoons.com/zh-hant/genres/fantasy?sortOrder=MANA&page=1",
+			"https://www.webtoons.com/zh-hant/genres/fantasy?sortOrder=MANA&page=2",
+			"https://www.webtoons.com/zh-hant/genres/romance_m?sortOrder=MANA&page=1",
+		]
+	);
+
+	// Later pages of the same filters don't fetch the genres again
+	client.search(&cache, &genres, None, 2, filters(&selected)).expect("second page");
+	assert_eq!(client.transport.requests().len(), 3);
+
+	// Unknown genres fail, as do only dropped ones rather than listing everything
+	assert!(client.search(&cache, &genres, None, 1, filters(&["已改名的類型"])).is_err());
+	assert!(client.search(&cache, &genres, None, 1, filters(&["驚悚"])).is_err());
+	assert_eq!(client.transport.requests().len(), 3);
+}
+
+#[test]
+fn details_from_api_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("1532"),
+		..Default::default()
+	};
+	let manga = client.manga_update(manga, true, false).expect("manga update");
+
+	assert_eq!(manga.title, "騎士與淑女");
+	assert!(matches!(manga.content_rating, ContentRating::NSFW));
+	assert!(manga.chapters.is_none());
+	assert_eq!(client.transport.requests(), ["https://m.webtoons.com/api/v1/webtoon/1532"]);
+}
+
+#[test]
+fn chapter_without_url_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("2089"),
+		..Default::default()
+	};
+	let chapter = Chapter {
+		key: String::from("2089/3"),
+		..Default::default()
+	};
+
+	// The viewer URL is rebuilt from the key, and the age gate is reported
+	assert!(client.page_list(&manga, &chapter, None).is_err());
+	assert_eq!(
+		client.transport.requests(),
+		["https://www.webtoons.com/zh-hant/originals/a/a/viewer?title_no=2089&episode_no=3"]
+	);
+}
+
+#[test]
+fn stale_chapter_url_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("2089"),
+		..Default::default()
+	};
+	let rebuilt = "https://www.webtoons.com/zh-hant/originals/a/a/viewer?title_no=2089&episode_no=1";
+
+	// A stored link for another episode isn't used
+	let chapter = Chapter {
+		key: String::from("2089/1"),
+		url: Some(String::from(
+			"https://www.webtoons.com/zh-hant/fantasy/omniscient-reader/ep-3/viewer?title_no=2089&episode_no=3",
+		)),
+		..Default::default()
+	};
+	assert_eq!(client.page_list(&manga, &chapter, None).expect("pages").len(), 3);
+	assert_eq!(client.transport.requests(), [rebuilt]);
+
+	// A link broken by a slug rename is retried with the rebuilt URL
+	let old_url = "https://www.webtoons.com/zh-hant/fantasy/old-slug/ep-1/viewer?title_no=2089&episode_no=1";
+	let chapter = Chapter {
+		key: String::from("2089/1"),
+		url: Some(String::from(old_url)),
+		..Default::default()
+	};
+	assert_eq!(client.page_list(&manga, &chapter, None).expect("pages").len(), 3);
+	assert_eq!(client.transport.requests()[1..], [old_url, rebuilt]);
+}
+
+#[test]
+fn age_gated_details_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("3000"),
+		title: String::from("大人系作品"),
+		..Default::default()
+	};
+
+	// Known details are kept and the age gate marks the title NSFW
+	let manga = client.manga_update(manga, true, false).expect("manga update");
+	assert_eq!(manga.title, "大人系作品");
+	assert!(matches!(manga.content_rating, ContentRating::NSFW));
+
+	// Without any known details, the age gate is reported
+	let unknown = Manga {
+		key: String::from("3000"),
+		..Default::default()
+	};
+	assert!(client.manga_update(unknown, true, false).is_err());
+}
+
+#[test]
+fn consent_page_details_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("3001"),
+		title: String::from("一般作品"),
+		content_rating: ContentRating::Safe,
+		..Default::default()
+	};
+
+	// A consent page says nothing about the title, so its rating is kept
+	let manga = client.manga_update(manga, true, false).expect("manga update");
+	assert_eq!(manga.title, "一般作品");
+	assert!(matches!(manga.content_rating, ContentRating::Safe));
+
+	let unknown = Manga {
+		key: String::from("3001"),
+		..Default::default()
+	};
+	assert!(client.manga_update(unknown, true, false).is_err());
+}
+
+#[test]
+fn removed_title_flow() {
+	let client = replay_client();
+	let manga = Manga {
+		key: String::from("404"),
+		..Default::default()
+	};
+
+	assert!(client.manga_update(manga, true, false).is_err());
+	assert_eq!(client.transport.requests().len(), 2);
+}
+
+#[test]
+fn unrecorded_request() {
+	let client = replay_client();
+	assert!(client
+		.fetch_manga_list("https://www.webtoons.com/zh-hant/originals/monday?sortOrder=MANA")
+		.is_err());
+}
+
+#[test]
+fn genre_rankings() {
+	let listings = crate::genre_ranking_listings(LANG, &static_genres(LANG));
+	assert_eq!(listings.len(), LANG.genres.len());
+	assert_eq!(listings[0].id, "ranking_genre:romance");
+	assert_eq!(listings[0].name, "人氣排行 · 愛情");
+	assert_eq!(
+		crate::ranking_url(LANG, &listings[0].id, 2).as_deref(),
+		Some("https://www.webtoons.com/zh-hant/ranking?sortOrder=MANA&genre=ROMANCE&page=2")
+	);
+	assert_eq!(crate::ranking_url(LANG, "ranking_genre:", 1), None);
+}
+
+#[test]
+fn image_quality() {
+	let page = "https://webtoon-phinf.pstatic.net/20240101_1/001.jpg?type=q90";
+	let cover = "https://webtoon-phinf.pstatic.net/20200602_1/thumb.jpg?type=a160&x=1";
+	let original = "https://webtoon-phinf.pstatic.net/20240101_1/001.jpg";
+	let other = "https://webtoons-static.pstatic.net/image/bg.png?type=q90";
+
+	assert_eq!(apply_image_quality(page, ImageQuality::High), page);
+	assert_eq!(apply_image_quality(page, ImageQuality::Original), original);
+	assert_eq!(
+		apply_image_quality(cover, ImageQuality::Original),
+		"https://webtoon-phinf.pstatic.net/20200602_1/thumb.jpg?x=1"
+	);
+
+	assert_eq!(
+		apply_image_quality(page, ImageQuality::DataSaver),
+		"https://webtoon-phinf.pstatic.net/20240101_1/001.jpg?type=q50"
+	);
+	assert_eq!(
+		apply_image_quality(original, ImageQuality::DataSaver),
+		"https://webtoon-phinf.pstatic.net/20240101_1/001.jpg?type=q50"
+	);
+	assert_eq!(
+		apply_image_quality(cover, ImageQuality::DataSaver),
+		"https://webtoon-phinf.pstatic.net/20200602_1/thumb.jpg?x=1&type=a160"
+	);
+
+	assert_eq!(apply_image_quality(other, ImageQuality::Original), other);
+	assert!(ImageQuality::from_setting("data_saver") == ImageQuality::DataSaver);
+	assert!(ImageQuality::from_setting("") == ImageQuality::High);
+}
+
+#[test]
+fn tile_context() {
+	let mut context = aidoku::PageContext::new();
+	assert_eq!(page_tile(&context), None);
+	context.insert(String::from(TILE_CONTEXT_KEY), String::from("1/3"));
+	assert_eq!(page_tile(&context), Some((1, 3)));
+	context.insert(String::from(TILE_CONTEXT_KEY), String::from("3/3"));
+	assert_eq!(page_tile(&context), None);
+
+	assert_eq!(tile_bounds(0.0, 9000.0, 0, 3), (0.0, 3000.0));
+	assert_eq!(tile_bounds(0.0, 9000.0, 2, 3), (6000.0, 9000.0));
+	assert_eq!(tile_bounds(100.0, 1100.0, 1, 2), (600.0, 1100.0));
+	assert_eq!(tile_bounds(0.0, 100.0, 0, 1), (0.0, 100.0));
+}
+
+#[test]
+fn margin_trimming() {
+	let width = 4;
+	let row = |values: [u8; 4]| values.to_vec();
+	let image: Vec<u8> = [
+		row([255, 255, 250, 255]),
+		row([255, 255, 255, 255]),
+		row([255, 40, 200, 255]),
+		row([0, 0, 0, 0]),
+		row([30, 120, 90, 10]),
+		row([5, 0, 0, 12]),
+	]
+	.concat();
+	assert_eq!(content_rows(&image, width, 6), (2, 5));
+
+	// Blank spacer images are kept whole
+	assert_eq!(content_rows(&[255; 8], width, 2), (0, 2));
+	assert_eq!(content_rows(&[0; 8], width, 2), (0, 2));
+	// Truncated data isn't trimmed
+	assert_eq!(content_rows(&[255; 4], width, 2), (0, 2));
+}
+
+#[test]
+fn page_image_processing() {
+	// 16×48 JPEG and PNG with a gray band on rows 16..32 between white margins
+	let jpeg = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/margins.jpg"));
+	let png = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/margins.png"));
+
+	let content = image_content_rows(jpeg);
+	assert_eq!(content, Some((16, 32, 48)));
+	assert_eq!(page_crop(48.0, None, content), Some((16.0, 32.0)));
+	assert_eq!(page_crop(48.0, Some((1, 2)), content), Some((24.0, 32.0)));
+	// Bounds measured on one copy apply to a resized one
+	assert_eq!(page_crop(24.0, None, content), Some((8.0, 16.0)));
+	assert_eq!(page_crop(24.0, Some((0, 2)), content), Some((8.0, 12.0)));
+
+	// Whole pages without trimming are passed through
+	assert_eq!(page_crop(48.0, None, None), None);
+	assert_eq!(page_crop(48.0, Some((1, 2)), None), Some((24.0, 48.0)));
+	// Blank images aren't cropped
+	assert_eq!(page_crop(48.0, None, Some((0, 48, 48))), None);
+
+	// PNG and WebP pages aren't decoded, so they're tiled but not trimmed
+	assert_eq!(image_content_rows(png), None);
+	assert_eq!(image_content_rows(b"RIFF\x1a\0\0\0WEBPVP8L"), None);
+	assert_eq!(page_crop(48.0, None, image_content_rows(png)), None);
+
+	// Tiles of one image decode it once
+	let cache = ContentRowsCache::default();
+	let decodes = std::cell::Cell::new(0);
+	let measure = || {
+		decodes.set(decodes.get() + 1);
+		image_content_rows(jpeg)
+	};
+	for _ in 0..3 {
+		assert_eq!(cache.get("003.jpg", measure), content);
+	}
+	assert_eq!(decodes.get(), 1);
+	assert_eq!(cache.get("004.jpg", measure), content);
+	assert_eq!(decodes.get(), 2);
+}
@@ -0,0 +1,93 @@
+use aidoku::{alloc::String, Result};
+
+use crate::node::Node;
+
+/// A response as seen by the source, however it was fetched.
+#[derive(Clone)]
+pub struct HttpResponse {
+	pub status: i32,
+	/// The body as text; empty for error responses.
+	pub body: String,
+}
+
+/// Where the source's GET requests go.
+///
+/// The app sends them through [`AidokuTransport`](crate::net::AidokuTransport),
+/// while tests answer them from recorded responses.
+pub trait Transport {
+	/// The HTML document type the transport parses pages into.
+	type Document: Node;
+
+	/// Fetch a URL.
+	fn get(&self, url: &str) -> Result<HttpResponse>;
+
+	/// Parse an HTML page fetched from `url`.
+	fn parse_html(&self, url: &str, body: &str) -> Result<Self::Document>;
+}
+
+#[cfg(test)]
+pub use replay::{Exchange, ReplayTransport};
+
+#[cfg(test)]
+mod replay {
+	use core::cell::RefCell;
+
+	use aidoku::{
+		alloc::{String, Vec},
+		prelude::*,
+		Result,
+	};
+
+	use super::{HttpResponse, Transport};
+	use crate::node::Node;
+
+	/// A recorded request/response pair.
+	pub struct Exchange {
+		pub url: String,
+		pub response: HttpResponse,
+	}
+
+	/// A transport that answers requests from recorded exchanges, for running
+	/// whole flows offline.
+	///
+	/// Requests are matched by exact URL, and requests without a recording
+	/// fail so missing fixtures show up as errors instead of live traffic.
+	pub struct ReplayTransport<D> {
+		exchanges: Vec<Exchange>,
+		parse: fn(&str, &str) -> Result<D>,
+		requests: RefCell<Vec<String>>,
+	}
+
+	impl<D: Node> ReplayTransport<D> {
+		/// Replay `exchanges`, parsing HTML pages with `parse(url, body)`.
+		pub fn new(exchanges: Vec<Exchange>, parse: fn(&str, &str) -> Result<D>) -> Self {
+			Self {
+				exchanges,
+				parse,
+				requests: RefCell::new(Vec::new()),
+			}
+		}
+
+		/// The URLs requested so far, in order.
+		pub fn requests(&self) -> Vec<String> {
+			self.requests.borrow().clone()
+		}
+	}
+
+	impl<D: Node> Transport for ReplayTransport<D> {
+		type Document = D;
+
+		fn get(&self, url: &str) -> Result<HttpResponse> {
+			self.requests.borrow_mut().push(String::from(url));
+			self.exchanges
+				.iter()
+				.find(|exchange: &&Exchange| exchange.url == url)
+				.map(|exchange: &Exchange| exchange.response.clone())
+				.ok_or_else(|| error!("No recorded response for {url}"))
+		}
+
+		fn parse_html(&self, url: &str, body: &str) -> Result<D> {
+			(self.parse)(url, body)
+		}
+	}
+}